@@ -8,12 +8,19 @@ use serde::de::{
 use crate::error::{Error, Result};
 use crate::internal::{InternalPrompt, ScopeLimit};
 use crate::internal_prompt_requester_mixin;
+use crate::path::ScopePath;
 use crate::prompt::{
   PromptRequester, PromptResponder, ReportKind, RequestKind,
 };
 use crate::u8i8;
 use crate::util;
 
+// The questions asked within bytes, options, seqs, and maps
+pub(crate) const ADD_BYTE: &str = "Add byte?";
+pub(crate) const SOME_VALUE: &str = "Some value?";
+pub(crate) const ADD_ELEMENT: &str = "Add element?";
+pub(crate) const ADD_ENTRY: &str = "Add entry?";
+
 pub struct Deserializer<P: PromptRequester> {
   prompt: InternalPrompt<P>,
 }
@@ -29,6 +36,14 @@ impl<P: PromptRequester> Deserializer<P> {
     self.prompt.cleanup()
   }
 
+  pub(crate) fn record_scope_limits(&mut self) {
+    self.prompt.record_limits()
+  }
+
+  pub(crate) fn take_scope_limits(&mut self) -> Vec<ScopeLimit> {
+    self.prompt.take_limits()
+  }
+
   fn report_bad_response(&mut self, msg: &str) -> Result<()> {
     self.report(ReportKind::BadResponse, msg)
   }
@@ -37,6 +52,10 @@ impl<P: PromptRequester> Deserializer<P> {
     util::ask_yes_no(&mut self.prompt, prompt)
   }
 
+  fn path(&self) -> ScopePath {
+    self.prompt.path().clone()
+  }
+
   internal_prompt_requester_mixin!(prompt);
 }
 
@@ -125,7 +144,7 @@ impl<'de, 'a, P: PromptRequester> de::Deserializer<'de>
   {
     let mut buf = Vec::<u8>::new();
     self.begin_scope("bytes", None, ScopeLimit::Explicit)?;
-    while self.ask_yes_no("Add byte?")? {
+    while self.ask_yes_no(ADD_BYTE)? {
       loop {
         let s = self.request(RequestKind::Datum, "u8", u8i8::U8_VARIANTS)?;
         match u8::from_str(&s) {
@@ -151,7 +170,7 @@ impl<'de, 'a, P: PromptRequester> de::Deserializer<'de>
     V: Visitor<'de>,
   {
     self.begin_scope("option", None, ScopeLimit::Implicit)?;
-    if self.ask_yes_no("Some value?")? {
+    if self.ask_yes_no(SOME_VALUE)? {
       visitor.visit_some(self)
     } else {
       self.end_implicit_scopes()?;
@@ -199,7 +218,7 @@ impl<'de, 'a, P: PromptRequester> de::Deserializer<'de>
     V: Visitor<'de>,
   {
     self.begin_scope("seq", None, ScopeLimit::Explicit)?;
-    let res = visitor.visit_seq(Seq::new(self))?;
+    let res = at_scope(self.path(), visitor.visit_seq(Seq::new(self)))?;
     self.end_scope()?;
     Ok(res)
   }
@@ -209,7 +228,7 @@ impl<'de, 'a, P: PromptRequester> de::Deserializer<'de>
     V: Visitor<'de>,
   {
     self.begin_scope("tuple", Some(len), ScopeLimit::Explicit)?;
-    let res = visitor.visit_seq(Tuple::new(self, len))?;
+    let res = at_scope(self.path(), visitor.visit_seq(Tuple::new(self, len)))?;
     self.end_scope()?;
     Ok(res)
   }
@@ -224,7 +243,7 @@ impl<'de, 'a, P: PromptRequester> de::Deserializer<'de>
     V: Visitor<'de>,
   {
     self.begin_scope(name, Some(len), ScopeLimit::Explicit)?;
-    let res = visitor.visit_seq(Tuple::new(self, len))?;
+    let res = at_scope(self.path(), visitor.visit_seq(Tuple::new(self, len)))?;
     self.end_scope()?;
     Ok(res)
   }
@@ -234,7 +253,7 @@ impl<'de, 'a, P: PromptRequester> de::Deserializer<'de>
     V: Visitor<'de>,
   {
     self.begin_scope("map", None, ScopeLimit::Explicit)?;
-    let res = visitor.visit_map(Map::new(self))?;
+    let res = at_scope(self.path(), visitor.visit_map(Map::new(self)))?;
     self.end_scope()?;
     Ok(res)
   }
@@ -249,7 +268,8 @@ impl<'de, 'a, P: PromptRequester> de::Deserializer<'de>
     V: Visitor<'de>,
   {
     self.begin_scope(name, Some(fields.len()), ScopeLimit::Explicit)?;
-    let res = visitor.visit_map(Struct::new(self, fields))?;
+    let res =
+      at_scope(self.path(), visitor.visit_map(Struct::new(self, fields)))?;
     self.end_scope()?;
     Ok(res)
   }
@@ -264,7 +284,8 @@ impl<'de, 'a, P: PromptRequester> de::Deserializer<'de>
     V: Visitor<'de>,
   {
     self.begin_scope(name, None, ScopeLimit::Explicit)?;
-    let res = visitor.visit_enum(Enum::new(self, variants))?;
+    let res =
+      at_scope(self.path(), visitor.visit_enum(Enum::new(self, variants)))?;
     self.end_scope()?;
     Ok(res)
   }
@@ -288,6 +309,15 @@ impl<'de, 'a, P: PromptRequester> de::Deserializer<'de>
   }
 }
 
+/// Attributes a Serde error not yet attributed to any scope to the scope at
+/// `path`.
+fn at_scope<T>(path: ScopePath, res: Result<T>) -> Result<T> {
+  res.map_err(|e| match e {
+    Error::SerdeError(msg, None) => Error::SerdeError(msg, Some(path)),
+    e => e,
+  })
+}
+
 impl<P: PromptRequester> Drop for Deserializer<P> {
   fn drop(&mut self) {
     let _ = self.cleanup();
@@ -378,7 +408,8 @@ impl<'de: 'a, 'a, P: PromptRequester> VariantAccess<'de>
     self
       .de
       .begin_scope(self.variant, Some(len), ScopeLimit::Explicit)?;
-    let res = visitor.visit_seq(Tuple::new(self.de, len))?;
+    let res =
+      at_scope(self.de.path(), visitor.visit_seq(Tuple::new(self.de, len)))?;
     self.de.end_scope()?;
     Ok(res)
   }
@@ -429,7 +460,7 @@ impl<'de: 'a, 'a, P: PromptRequester> MapAccess<'de> for Struct<'a, P> {
   where
     V: DeserializeSeed<'de>,
   {
-    seed.deserialize(&mut *self.de)
+    at_scope(self.de.path(), seed.deserialize(&mut *self.de))
   }
 }
 
@@ -457,8 +488,8 @@ impl<'de: 'a, 'a, P: PromptRequester> SeqAccess<'de> for Seq<'a, P> {
       ScopeLimit::Implicit,
     )?;
     self.index += 1;
-    if self.de.ask_yes_no("Add element?")? {
-      seed.deserialize(&mut *self.de).map(Some)
+    if self.de.ask_yes_no(ADD_ELEMENT)? {
+      at_scope(self.de.path(), seed.deserialize(&mut *self.de).map(Some))
     } else {
       self.de.end_implicit_scopes()?;
       Ok(None)
@@ -492,7 +523,7 @@ impl<'de, 'a, P: PromptRequester> SeqAccess<'de> for Tuple<'a, P> {
     )?;
     if self.index < self.len {
       self.index += 1;
-      seed.deserialize(&mut *self.de).map(Some)
+      at_scope(self.de.path(), seed.deserialize(&mut *self.de).map(Some))
     } else {
       self.de.end_implicit_scopes()?;
       Ok(None)
@@ -524,8 +555,8 @@ impl<'de: 'a, 'a, P: PromptRequester> MapAccess<'de> for Map<'a, P> {
       ScopeLimit::Explicit,
     )?;
     self.index += 1;
-    if self.de.ask_yes_no("Add entry?")? {
-      seed.deserialize(&mut *self.de).map(Some)
+    if self.de.ask_yes_no(ADD_ENTRY)? {
+      at_scope(self.de.path(), seed.deserialize(&mut *self.de).map(Some))
     } else {
       self.de.end_scope()?;
       Ok(None)
@@ -536,7 +567,7 @@ impl<'de: 'a, 'a, P: PromptRequester> MapAccess<'de> for Map<'a, P> {
   where
     V: DeserializeSeed<'de>,
   {
-    let res = seed.deserialize(&mut *self.de)?;
+    let res = at_scope(self.de.path(), seed.deserialize(&mut *self.de))?;
     self.de.end_scope()?;
     Ok(res)
   }
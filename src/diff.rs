@@ -0,0 +1,224 @@
+use crate::de::ADD_ENTRY;
+use crate::error::Result;
+use crate::path::{PathEntry, ScopePath};
+use crate::prompt::{PromptResponder, RequestKind};
+use std::collections::{HashMap, HashSet};
+
+/// A difference between the responses given for two values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+  /// A response given only for the new value.
+  Added {
+    path: ScopePath,
+    prompt: String,
+    value: String,
+  },
+  /// A response given only for the old value.
+  Removed {
+    path: ScopePath,
+    prompt: String,
+    value: String,
+  },
+  /// A response which differs between the old and new values.
+  Changed {
+    path: ScopePath,
+    prompt: String,
+    old: String,
+    new: String,
+  },
+}
+
+impl Change {
+  /// Returns the path of the scope in which the response was given.
+  pub fn path(&self) -> &ScopePath {
+    match self {
+      Change::Added { path, .. }
+      | Change::Removed { path, .. }
+      | Change::Changed { path, .. } => path,
+    }
+  }
+}
+
+/// A datum identified by its path, prompt, and occurrence within its scope.
+struct Datum<'a> {
+  key: (ScopePath, &'a str, usize),
+  value: &'a str,
+}
+
+/// Collects the data among `entries`, naming map entries by their keys so
+/// that they line up regardless of their position.
+fn data(entries: &[PathEntry]) -> Vec<Datum<'_>> {
+  let mut data: Vec<Datum> = Vec::new();
+  // The entry whose key is yet to be seen, and entries renamed after keys
+  let mut pending: Option<ScopePath> = None;
+  let mut renames: Vec<(ScopePath, ScopePath)> = Vec::new();
+  // The number of data seen so far with each path and prompt
+  let mut counts: HashMap<(ScopePath, &str), usize> = HashMap::new();
+  for entry in entries {
+    let mut path = entry.path.clone();
+    for (from, to) in renames.iter().rev() {
+      if path.starts_with(from) {
+        let mut renamed = to.clone();
+        for name in &path.names()[from.len()..] {
+          renamed.push(name);
+        }
+        path = renamed;
+        break;
+      }
+    }
+    match entry.kind {
+      RequestKind::Question if entry.prompt == ADD_ENTRY => {
+        if entry.response == "yes" {
+          pending = Some(entry.path.clone());
+        }
+        continue;
+      }
+      RequestKind::Datum => {}
+      _ => continue,
+    }
+    if let Some(from) = pending.take() {
+      if entry.path.starts_with(&from) {
+        let mut to = from.prefix(from.len() - 1);
+        to.push(&format!("[{}]", entry.response));
+        path = to.clone();
+        for name in &entry.path.names()[from.len()..] {
+          path.push(name);
+        }
+        renames.push((from, to));
+      }
+    }
+    let count = counts.entry((path.clone(), &entry.prompt)).or_insert(0);
+    let n = *count;
+    *count += 1;
+    data.push(Datum {
+      key: (path, &entry.prompt, n),
+      value: &entry.response,
+    });
+  }
+  data
+}
+
+/// Aligns the data of two recordings, returning their differences in order.
+pub(crate) fn diff_entries(
+  old: &[PathEntry],
+  new: &[PathEntry],
+) -> Vec<Change> {
+  let old = data(old);
+  let new = data(new);
+  let mut old_keys: HashSet<_> = old.iter().map(|d| &d.key).collect();
+  let new_keys: HashSet<_> = new.iter().map(|d| &d.key).collect();
+  let mut changes = Vec::new();
+  let removed = |d: &Datum| Change::Removed {
+    path: d.key.0.clone(),
+    prompt: d.key.1.to_string(),
+    value: d.value.to_string(),
+  };
+  let (mut i, mut j) = (0, 0);
+  while i < old.len() || j < new.len() {
+    match (old.get(i), new.get(j)) {
+      (Some(o), Some(n)) if o.key == n.key => {
+        if o.value != n.value {
+          changes.push(Change::Changed {
+            path: n.key.0.clone(),
+            prompt: n.key.1.to_string(),
+            old: o.value.to_string(),
+            new: n.value.to_string(),
+          });
+        }
+        old_keys.remove(&o.key);
+        i += 1;
+        j += 1;
+      }
+      (Some(o), _) if !new_keys.contains(&o.key) => {
+        changes.push(removed(o));
+        old_keys.remove(&o.key);
+        i += 1;
+      }
+      (_, Some(n)) if !old_keys.contains(&n.key) => {
+        changes.push(Change::Added {
+          path: n.key.0.clone(),
+          prompt: n.key.1.to_string(),
+          value: n.value.to_string(),
+        });
+        j += 1;
+      }
+      // Both data appear later on in the other recording
+      (Some(o), _) => {
+        changes.push(removed(o));
+        old_keys.remove(&o.key);
+        i += 1;
+      }
+      (None, _) => unreachable!(),
+    }
+  }
+  changes
+}
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// Sends changes to a responder as a unified view, nested within their scopes.
+///
+/// Removed responses are sent with their prompts prefixed with `-` and added
+/// ones with `+`, while a changed response is sent as both. If `colour` is
+/// true, removed responses are coloured red and added ones green using ANSI
+/// escape codes.
+pub fn write_diff<P: PromptResponder>(
+  changes: &[Change],
+  mut prompt: P,
+  colour: bool,
+) -> Result<()> {
+  let mut current = ScopePath::new();
+  let line = |prompt: &mut P, sign: char, name: &str, value: &str| {
+    let (start, end) = match (colour, sign) {
+      (false, _) => ("", ""),
+      (true, '-') => (RED, RESET),
+      (true, _) => (GREEN, RESET),
+    };
+    let name = format!("{}{}{}", start, sign, name);
+    prompt.respond(RequestKind::Datum, &name, &format!("{}{}", value, end))
+  };
+  for change in changes {
+    let path = change.path();
+    let common = current
+      .names()
+      .iter()
+      .zip(path.names())
+      .take_while(|(a, b)| a == b)
+      .count();
+    while current.len() > common {
+      current.pop();
+      prompt.end_scope()?;
+    }
+    for name in &path.names()[common..] {
+      current.push(name);
+      prompt.begin_scope(name, None)?;
+    }
+    match change {
+      Change::Added {
+        prompt: name,
+        value,
+        ..
+      } => line(&mut prompt, '+', name, value)?,
+      Change::Removed {
+        prompt: name,
+        value,
+        ..
+      } => line(&mut prompt, '-', name, value)?,
+      Change::Changed {
+        prompt: name,
+        old,
+        new,
+        ..
+      } => {
+        line(&mut prompt, '-', name, old)?;
+        line(&mut prompt, '+', name, new)?;
+      }
+    }
+  }
+  for _ in 0..current.len() {
+    prompt.end_scope()?;
+  }
+  Ok(())
+}
@@ -0,0 +1,814 @@
+use serde::ser::{self, Serialize};
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use crate::error::{Error, Result};
+use crate::prompt::{
+  PromptRequester, PromptResponder, ReportKind, RequestKind,
+};
+
+/// Tree of values making up a document.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Node {
+  Null,
+  Value(String),
+  Seq(Vec<Node>),
+  Map(Vec<(Node, Node)>),
+}
+
+/// Tree which values can be serialised into by `NodeSerializer`.
+pub(crate) trait Build: Sized {
+  fn value(v: String) -> Self;
+  fn bytes(v: &[u8]) -> Self;
+  fn none() -> Self;
+  fn unit() -> Self;
+  fn unit_struct(name: &'static str) -> Self;
+  fn unit_variant(variant: &'static str) -> Self;
+  /// Wraps the contents of a newtype, tuple, or struct variant.
+  fn variant(variant: &'static str, inner: Self) -> Self;
+  fn seq(elements: Vec<Self>) -> Self;
+  fn tuple(elements: Vec<Self>) -> Self;
+  fn map(entries: Vec<(Self, Self)>) -> Self;
+  fn fields(fields: Vec<(&'static str, Self)>) -> Self;
+}
+
+impl Build for Node {
+  fn value(v: String) -> Self {
+    Node::Value(v)
+  }
+
+  fn bytes(v: &[u8]) -> Self {
+    Node::Seq(v.iter().map(|b| Node::Value(b.to_string())).collect())
+  }
+
+  fn none() -> Self {
+    Node::Null
+  }
+
+  fn unit() -> Self {
+    Node::Null
+  }
+
+  fn unit_struct(_name: &'static str) -> Self {
+    Node::Null
+  }
+
+  fn unit_variant(variant: &'static str) -> Self {
+    Node::Value(variant.to_string())
+  }
+
+  fn variant(variant: &'static str, inner: Self) -> Self {
+    Node::Map(vec![(Node::Value(variant.to_string()), inner)])
+  }
+
+  fn seq(elements: Vec<Self>) -> Self {
+    Node::Seq(elements)
+  }
+
+  fn tuple(elements: Vec<Self>) -> Self {
+    Node::Seq(elements)
+  }
+
+  fn map(entries: Vec<(Self, Self)>) -> Self {
+    Node::Map(entries)
+  }
+
+  fn fields(fields: Vec<(&'static str, Self)>) -> Self {
+    Node::Map(
+      fields
+        .into_iter()
+        .map(|(k, v)| (Node::Value(k.to_string()), v))
+        .collect(),
+    )
+  }
+}
+
+/// Serialiser which converts any serialisable value into a tree.
+struct NodeSerializer<N>(PhantomData<N>);
+
+impl<N> NodeSerializer<N> {
+  fn new() -> Self {
+    NodeSerializer(PhantomData)
+  }
+}
+
+/// Converts a serialisable document into a `Node`.
+pub(crate) fn to_node<D: Serialize + ?Sized>(document: &D) -> Result<Node> {
+  build(document)
+}
+
+/// Converts a serialisable value into a tree of any kind.
+pub(crate) fn build<N: Build, T: Serialize + ?Sized>(value: &T) -> Result<N> {
+  value.serialize(NodeSerializer::new())
+}
+
+macro_rules! serialize_to_value {
+  ($tname:ty, $smethod:ident) => {
+    fn $smethod(self, v: $tname) -> Result<N> {
+      Ok(N::value(v.to_string()))
+    }
+  };
+}
+
+impl<N: Build> ser::Serializer for NodeSerializer<N> {
+  type Ok = N;
+  type Error = Error;
+
+  type SerializeSeq = Collector<N>;
+  type SerializeTuple = Collector<N>;
+  type SerializeTupleStruct = Collector<N>;
+  type SerializeTupleVariant = Collector<N>;
+  type SerializeMap = Collector<N>;
+  type SerializeStruct = Collector<N>;
+  type SerializeStructVariant = Collector<N>;
+
+  serialize_to_value!(bool, serialize_bool);
+  serialize_to_value!(u8, serialize_u8);
+  serialize_to_value!(u16, serialize_u16);
+  serialize_to_value!(u32, serialize_u32);
+  serialize_to_value!(u64, serialize_u64);
+  serialize_to_value!(u128, serialize_u128);
+  serialize_to_value!(i8, serialize_i8);
+  serialize_to_value!(i16, serialize_i16);
+  serialize_to_value!(i32, serialize_i32);
+  serialize_to_value!(i64, serialize_i64);
+  serialize_to_value!(i128, serialize_i128);
+  serialize_to_value!(f32, serialize_f32);
+  serialize_to_value!(f64, serialize_f64);
+  serialize_to_value!(char, serialize_char);
+  serialize_to_value!(&str, serialize_str);
+
+  fn serialize_bytes(self, v: &[u8]) -> Result<N> {
+    Ok(N::bytes(v))
+  }
+
+  fn serialize_none(self) -> Result<N> {
+    Ok(N::none())
+  }
+
+  fn serialize_some<T>(self, value: &T) -> Result<N>
+  where
+    T: ?Sized + Serialize,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> Result<N> {
+    Ok(N::unit())
+  }
+
+  fn serialize_unit_struct(self, name: &'static str) -> Result<N> {
+    Ok(N::unit_struct(name))
+  }
+
+  fn serialize_unit_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+  ) -> Result<N> {
+    Ok(N::unit_variant(variant))
+  }
+
+  fn serialize_newtype_struct<T>(
+    self,
+    _name: &'static str,
+    value: &T,
+  ) -> Result<N>
+  where
+    T: ?Sized + Serialize,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T>(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    value: &T,
+  ) -> Result<N>
+  where
+    T: ?Sized + Serialize,
+  {
+    Ok(N::variant(variant, value.serialize(self)?))
+  }
+
+  fn serialize_seq(self, _len: Option<usize>) -> Result<Collector<N>> {
+    Ok(Collector::new(None))
+  }
+
+  fn serialize_tuple(self, _len: usize) -> Result<Collector<N>> {
+    Ok(Collector::new(None))
+  }
+
+  fn serialize_tuple_struct(
+    self,
+    _name: &'static str,
+    _len: usize,
+  ) -> Result<Collector<N>> {
+    Ok(Collector::new(None))
+  }
+
+  fn serialize_tuple_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    _len: usize,
+  ) -> Result<Collector<N>> {
+    Ok(Collector::new(Some(variant)))
+  }
+
+  fn serialize_map(self, _len: Option<usize>) -> Result<Collector<N>> {
+    Ok(Collector::new(None))
+  }
+
+  fn serialize_struct(
+    self,
+    _name: &'static str,
+    _len: usize,
+  ) -> Result<Collector<N>> {
+    Ok(Collector::new(None))
+  }
+
+  fn serialize_struct_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    _len: usize,
+  ) -> Result<Collector<N>> {
+    Ok(Collector::new(Some(variant)))
+  }
+}
+
+/// Collects the elements, entries, or fields of a compound value into a tree.
+struct Collector<N> {
+  variant: Option<&'static str>,
+  elements: Vec<N>,
+  entries: Vec<(N, N)>,
+  fields: Vec<(&'static str, N)>,
+  key: Option<N>,
+}
+
+impl<N: Build> Collector<N> {
+  fn new(variant: Option<&'static str>) -> Self {
+    Collector {
+      variant,
+      elements: Vec::new(),
+      entries: Vec::new(),
+      fields: Vec::new(),
+      key: None,
+    }
+  }
+
+  fn push_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+    self.elements.push(build(value)?);
+    Ok(())
+  }
+
+  fn push_field<T: ?Sized + Serialize>(
+    &mut self,
+    key: &'static str,
+    value: &T,
+  ) -> Result<()> {
+    self.fields.push((key, build(value)?));
+    Ok(())
+  }
+
+  fn wrap(self, node: N) -> Result<N> {
+    Ok(match self.variant {
+      Some(variant) => N::variant(variant, node),
+      None => node,
+    })
+  }
+
+  fn finish_seq(mut self) -> Result<N> {
+    let elements = std::mem::take(&mut self.elements);
+    self.wrap(N::seq(elements))
+  }
+
+  fn finish_tuple(mut self) -> Result<N> {
+    let elements = std::mem::take(&mut self.elements);
+    self.wrap(N::tuple(elements))
+  }
+
+  fn finish_map(mut self) -> Result<N> {
+    let entries = std::mem::take(&mut self.entries);
+    self.wrap(N::map(entries))
+  }
+
+  fn finish_fields(mut self) -> Result<N> {
+    let fields = std::mem::take(&mut self.fields);
+    self.wrap(N::fields(fields))
+  }
+}
+
+impl<N: Build> ser::SerializeSeq for Collector<N> {
+  type Ok = N;
+  type Error = Error;
+
+  fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.push_element(value)
+  }
+
+  fn end(self) -> Result<N> {
+    self.finish_seq()
+  }
+}
+
+impl<N: Build> ser::SerializeTuple for Collector<N> {
+  type Ok = N;
+  type Error = Error;
+
+  fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.push_element(value)
+  }
+
+  fn end(self) -> Result<N> {
+    self.finish_tuple()
+  }
+}
+
+impl<N: Build> ser::SerializeTupleStruct for Collector<N> {
+  type Ok = N;
+  type Error = Error;
+
+  fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.push_element(value)
+  }
+
+  fn end(self) -> Result<N> {
+    self.finish_tuple()
+  }
+}
+
+impl<N: Build> ser::SerializeTupleVariant for Collector<N> {
+  type Ok = N;
+  type Error = Error;
+
+  fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.push_element(value)
+  }
+
+  fn end(self) -> Result<N> {
+    self.finish_tuple()
+  }
+}
+
+impl<N: Build> ser::SerializeMap for Collector<N> {
+  type Ok = N;
+  type Error = Error;
+
+  fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.key = Some(build(key)?);
+    Ok(())
+  }
+
+  fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    let key = self.key.take().unwrap_or_else(N::none);
+    self.entries.push((key, build(value)?));
+    Ok(())
+  }
+
+  fn end(self) -> Result<N> {
+    self.finish_map()
+  }
+}
+
+impl<N: Build> ser::SerializeStruct for Collector<N> {
+  type Ok = N;
+  type Error = Error;
+
+  fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.push_field(key, value)
+  }
+
+  fn end(self) -> Result<N> {
+    self.finish_fields()
+  }
+}
+
+impl<N: Build> ser::SerializeStructVariant for Collector<N> {
+  type Ok = N;
+  type Error = Error;
+
+  fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+  where
+    T: ?Sized + Serialize,
+  {
+    self.push_field(key, value)
+  }
+
+  fn end(self) -> Result<N> {
+    self.finish_fields()
+  }
+}
+
+/// Position within the document corresponding to a scope.
+#[derive(Clone)]
+enum Cursor {
+  /// The document has no entry for the scope.
+  Missing,
+  /// The scope is an element or entry past the end of the document's.
+  End,
+  Node(Node),
+  /// The scope is a map entry, whose key has been given if `key_done`.
+  Entry(Node, Node, bool),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FrameKind {
+  /// A scope holding a value, such as a field or the root.
+  Value,
+  /// A scope named after a type, whose children are fields.
+  Type,
+  /// A scope which is either a type or a field missing from the document.
+  Unresolved,
+  Seq,
+  Map,
+  Option,
+  Element,
+}
+
+struct Frame {
+  kind: FrameKind,
+  cursor: Cursor,
+  variant: Option<String>,
+  /// Keys and indices leading to the scope's node in the document.
+  doc_path: Vec<String>,
+}
+
+impl Frame {
+  fn new(kind: FrameKind, cursor: Cursor, doc_path: Vec<String>) -> Self {
+    Frame {
+      kind,
+      cursor,
+      variant: None,
+      doc_path,
+    }
+  }
+
+  /// Returns the node which the children of this scope refer to.
+  fn node(&self) -> Option<&Node> {
+    match &self.cursor {
+      Cursor::Node(node) => Some(node),
+      Cursor::Entry(key, _, false) => Some(key),
+      Cursor::Entry(_, value, true) => Some(value),
+      _ => None,
+    }
+  }
+}
+
+fn element_index(name: &str) -> Option<usize> {
+  let inner = name.strip_prefix('[')?.strip_suffix(']')?;
+  match inner.split_once('/') {
+    Some((i, _)) => i.parse::<usize>().ok()?.checked_sub(1),
+    None => inner.parse().ok(),
+  }
+}
+
+fn with_key(path: &[String], key: &str) -> Vec<String> {
+  let mut path = path.to_vec();
+  path.push(key.to_string());
+  path
+}
+
+/// Appends the paths of the values within `node` to `leaves`.
+fn collect_leaves(node: &Node, path: &[String], leaves: &mut Vec<Vec<String>>) {
+  match node {
+    Node::Null | Node::Value(_) => leaves.push(path.to_vec()),
+    Node::Seq(elements) => {
+      for (i, element) in elements.iter().enumerate() {
+        collect_leaves(element, &with_key(path, &i.to_string()), leaves);
+      }
+    }
+    Node::Map(entries) => {
+      for (key, value) in entries {
+        if let Node::Value(key) = key {
+          collect_leaves(value, &with_key(path, key), leaves);
+        }
+      }
+    }
+  }
+}
+
+/// Prompt decorator which answers requests from a document.
+///
+/// The document may be any serialisable value, such as a JSON or TOML value
+/// loaded by another crate. Each request is answered by looking up the
+/// current scope path in the document, with structs and maps looked up by
+/// field name or key and seqs and tuples by index. The questions of whether to
+/// add an element or entry or whether an option has a value are answered from
+/// the shape of the document, and the variants of enums are looked up using
+/// the same representation as Serde's externally tagged enums.
+///
+/// Requests for which the document has no entry are passed to the inner
+/// prompt. Each request is answered from the document at most once, so that
+/// requests repeated after a bad response or after being undone with a
+/// `ReplayPrompt` are also passed to the inner prompt.
+pub struct DocumentPrompt<P> {
+  inner: P,
+  root: Node,
+  ignore_case: bool,
+  frames: Vec<Frame>,
+  path: Vec<String>,
+  answered: HashSet<(Vec<String>, bool, String)>,
+  used: HashSet<Vec<String>>,
+}
+
+impl<P> DocumentPrompt<P> {
+  pub fn new<D: Serialize + ?Sized>(document: &D, inner: P) -> Result<Self> {
+    Ok(DocumentPrompt::from_node(to_node(document)?, false, inner))
+  }
+
+  pub(crate) fn from_node(root: Node, ignore_case: bool, inner: P) -> Self {
+    let frame =
+      Frame::new(FrameKind::Value, Cursor::Node(root.clone()), vec![]);
+    DocumentPrompt {
+      inner,
+      root,
+      ignore_case,
+      frames: vec![frame],
+      path: Vec::new(),
+      answered: HashSet::new(),
+      used: HashSet::new(),
+    }
+  }
+
+  /// Returns the paths of the values in the document which haven't been used
+  /// to answer a request, with their keys and indices separated by full
+  /// stops.
+  pub fn unused(&self) -> Vec<String> {
+    let mut leaves = Vec::new();
+    collect_leaves(&self.root, &[], &mut leaves);
+    leaves
+      .into_iter()
+      .filter(|path| !self.is_used(path))
+      .map(|path| path.join("."))
+      .collect()
+  }
+
+  /// Returns true if the value at `path` has been used to answer a request.
+  pub(crate) fn is_used(&self, path: &[String]) -> bool {
+    self.used.contains(path)
+  }
+
+  /// Looks up `key` in `node`, returning the key as spelt in the document.
+  fn lookup<'a>(
+    &self,
+    node: &'a Node,
+    key: &str,
+  ) -> Option<(&'a str, &'a Node)> {
+    match node {
+      Node::Map(entries) => entries.iter().find_map(|(k, v)| match k {
+        Node::Value(k) if k == key => Some((k.as_str(), v)),
+        Node::Value(k) if self.ignore_case && k.eq_ignore_ascii_case(key) => {
+          Some((k.as_str(), v))
+        }
+        _ => None,
+      }),
+      _ => None,
+    }
+  }
+
+  fn child_frame(&self, name: &str) -> Frame {
+    let top = self.frames.last().unwrap();
+    let path = &top.doc_path;
+    let structural = match name {
+      "seq" | "tuple" => Some(FrameKind::Seq),
+      "map" => Some(FrameKind::Map),
+      "option" => Some(FrameKind::Option),
+      _ => None,
+    };
+    let missing = || {
+      let kind = match (structural, element_index(name)) {
+        (Some(kind), _) => kind,
+        (None, Some(_)) => FrameKind::Element,
+        (None, None) => FrameKind::Value,
+      };
+      Frame::new(kind, Cursor::Missing, path.clone())
+    };
+    let node = match top.node() {
+      Some(node) => node.clone(),
+      // The children of missing scopes are also missing
+      None => return missing(),
+    };
+    let field = |kind| match self.lookup(&node, name) {
+      Some((key, child)) => Frame::new(
+        FrameKind::Value,
+        Cursor::Node(child.clone()),
+        with_key(path, key),
+      ),
+      None => match kind {
+        FrameKind::Unresolved => {
+          Frame::new(kind, Cursor::Node(node.clone()), path.clone())
+        }
+        _ => missing(),
+      },
+    };
+    if top.kind == FrameKind::Unresolved {
+      // The scope was a type if this is one of its fields
+      return field(FrameKind::Value);
+    }
+    if let Some(kind) = structural {
+      return Frame::new(kind, Cursor::Node(node), path.clone());
+    }
+    if let Some(i) = element_index(name) {
+      let (cursor, path) = match &node {
+        Node::Seq(elements) => match elements.get(i) {
+          Some(element) => (
+            Cursor::Node(element.clone()),
+            with_key(path, &i.to_string()),
+          ),
+          None => (Cursor::End, path.clone()),
+        },
+        Node::Map(entries) => match entries.get(i) {
+          Some((Node::Value(k), v)) => (
+            Cursor::Entry(Node::Value(k.clone()), v.clone(), false),
+            with_key(path, k),
+          ),
+          Some((k, v)) => {
+            (Cursor::Entry(k.clone(), v.clone(), false), path.clone())
+          }
+          None => (Cursor::End, path.clone()),
+        },
+        // Null stands for an empty collection
+        Node::Null => (Cursor::End, path.clone()),
+        _ => (Cursor::Missing, path.clone()),
+      };
+      return Frame::new(FrameKind::Element, cursor, path);
+    }
+    match top.kind {
+      FrameKind::Type if top.variant.as_deref() == Some(name) => {
+        Frame::new(FrameKind::Type, Cursor::Node(node), path.clone())
+      }
+      FrameKind::Type => match &node {
+        // The variant may have been given by another prompt
+        Node::Map(entries) if entries.len() == 1 && top.variant.is_none() => {
+          match self.lookup(&node, name) {
+            Some((key, child)) => Frame::new(
+              FrameKind::Type,
+              Cursor::Node(child.clone()),
+              with_key(path, key),
+            ),
+            None => field(FrameKind::Unresolved),
+          }
+        }
+        _ => field(FrameKind::Unresolved),
+      },
+      _ => Frame::new(FrameKind::Type, Cursor::Node(node), path.clone()),
+    }
+  }
+
+  /// Returns the answer to a request from the document, if it has one.
+  fn answer(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    variants: &[&str],
+  ) -> Option<String> {
+    let ignore_case = self.ignore_case;
+    let top = self.frames.last_mut().unwrap();
+    let used = &mut self.used;
+    let yes_no = |b: bool| Some(if b { "yes" } else { "no" }.to_string());
+    match (kind, top.kind, &top.cursor) {
+      (RequestKind::Question, FrameKind::Element, Cursor::End) => yes_no(false),
+      (RequestKind::Question, FrameKind::Element, Cursor::Missing) => None,
+      (RequestKind::Question, FrameKind::Element, _) => yes_no(true),
+      (RequestKind::Question, FrameKind::Option, Cursor::Node(node)) => {
+        if *node == Node::Null {
+          used.insert(top.doc_path.clone());
+        }
+        yes_no(*node != Node::Null)
+      }
+      (RequestKind::Question, _, _) => None,
+      (_, FrameKind::Type, Cursor::Node(node)) if prompt == "variant" => {
+        let (variant, node) = match node {
+          Node::Value(variant) => {
+            used.insert(top.doc_path.clone());
+            (variant.clone(), Node::Null)
+          }
+          Node::Map(entries) if entries.len() == 1 => match &entries[0] {
+            (Node::Value(variant), value) => {
+              top.doc_path.push(variant.clone());
+              (variant.clone(), value.clone())
+            }
+            _ => return None,
+          },
+          _ => return None,
+        };
+        let variant = match variants.iter().find(|v| {
+          **v == variant || ignore_case && v.eq_ignore_ascii_case(&variant)
+        }) {
+          Some(v) => v.to_string(),
+          None => variant,
+        };
+        top.cursor = Cursor::Node(node);
+        top.variant = Some(variant.clone());
+        Some(variant)
+      }
+      (_, FrameKind::Unresolved, _) => None,
+      (_, _, Cursor::Entry(Node::Value(key), _, false)) => {
+        let key = key.clone();
+        if let Cursor::Entry(_, _, key_done) = &mut top.cursor {
+          *key_done = true;
+        }
+        Some(key)
+      }
+      (_, _, Cursor::Entry(_, Node::Value(value), true))
+      | (_, _, Cursor::Node(Node::Value(value))) => {
+        used.insert(top.doc_path.clone());
+        Some(value.clone())
+      }
+      _ => None,
+    }
+  }
+}
+
+impl<P: PromptResponder> PromptResponder for DocumentPrompt<P> {
+  fn begin_scope(&mut self, name: &str, size: Option<usize>) -> Result<()> {
+    let frame = self.child_frame(name);
+    self.frames.push(frame);
+    self.path.push(name.to_string());
+    self.inner.begin_scope(name, size)
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    if self.frames.len() > 1 {
+      self.frames.pop();
+    }
+    self.path.pop();
+    // A scope ending directly within a map entry was the entry's key
+    if let Some(Frame {
+      cursor: Cursor::Entry(_, _, key_done),
+      ..
+    }) = self.frames.last_mut()
+    {
+      *key_done = true;
+    }
+    self.inner.end_scope()
+  }
+
+  fn respond(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    response: &str,
+  ) -> Result<()> {
+    self.inner.respond(kind, prompt, response)
+  }
+}
+
+impl<P: PromptRequester> PromptRequester for DocumentPrompt<P> {
+  fn is_interactive(&self) -> bool {
+    self.inner.is_interactive()
+  }
+
+  fn request(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    variants: &'static [&'static str],
+  ) -> Result<String> {
+    let key_done = matches!(
+      self.frames.last().unwrap().cursor,
+      Cursor::Entry(_, _, true)
+    );
+    let id = (self.path.clone(), key_done, prompt.to_string());
+    if !self.answered.contains(&id) {
+      if let Some(response) = self.answer(kind, prompt, variants) {
+        self.answered.insert(id);
+        self.inner.respond(kind, prompt, &response)?;
+        return Ok(response);
+      }
+    }
+    self.inner.request(kind, prompt, variants)
+  }
+
+  fn report(&mut self, kind: ReportKind, msg: &str) -> Result<()> {
+    self.inner.report(kind, msg)
+  }
+}
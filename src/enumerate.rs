@@ -0,0 +1,262 @@
+use crate::error::{Error, Result};
+use crate::from_bare_prompt;
+use crate::prompt::{
+  PromptRequester, PromptResponder, ReportKind, RequestKind,
+};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+/// Enumerates every value of a type by deserialising it repeatedly, making a
+/// depth-first search over the answers to each question and variant request.
+///
+/// Answers which end a value, such as not adding an element or a `Some` value,
+/// are tried first. Seqs and maps are limited in length, and numbers, chars
+/// and strings take each of a few sample values. Once the number of open
+/// scopes reaches the depth limit, only the first answer to each question and
+/// sample request is tried, and enums aren't entered at all, so that every
+/// search ends however the type recurses.
+pub struct Enumerator {
+  max_length: usize,
+  max_depth: usize,
+  max_values: Option<usize>,
+  samples: HashMap<String, Vec<String>>,
+}
+
+fn default_samples(label: &str) -> Vec<String> {
+  let samples: &[&str] = match label {
+    "u8" => &["0", "1", "255"],
+    "u16" => &["0", "1", "65535"],
+    "u32" => &["0", "1", "4294967295"],
+    "u64" => &["0", "1", "18446744073709551615"],
+    "u128" => &["0", "1", "340282366920938463463374607431768211455"],
+    "i8" => &["-128", "-1", "0", "1", "127"],
+    "i16" => &["-32768", "-1", "0", "1", "32767"],
+    "i32" => &["-2147483648", "-1", "0", "1", "2147483647"],
+    "i64" => &[
+      "-9223372036854775808",
+      "-1",
+      "0",
+      "1",
+      "9223372036854775807",
+    ],
+    "i128" => &[
+      "-170141183460469231731687303715884105728",
+      "-1",
+      "0",
+      "1",
+      "170141183460469231731687303715884105727",
+    ],
+    "f32" | "f64" => &["0", "-1", "1"],
+    "char" => &["a"],
+    _ => &["", "a"],
+  };
+  samples.iter().map(|s| s.to_string()).collect()
+}
+
+impl Enumerator {
+  pub fn new() -> Self {
+    Enumerator {
+      max_length: 2,
+      max_depth: 16,
+      max_values: None,
+      samples: HashMap::new(),
+    }
+  }
+
+  /// Sets the maximum number of elements in seqs, maps and byte buffers.
+  /// Defaults to 2.
+  pub fn set_max_length(&mut self, max_length: usize) {
+    self.max_length = max_length;
+  }
+
+  /// Sets the number of open scopes, including those of fields, beyond which
+  /// only the first answer is tried. Defaults to 16.
+  pub fn set_max_depth(&mut self, max_depth: usize) {
+    self.max_depth = max_depth;
+  }
+
+  /// Sets the number of values after which to stop enumerating, if any.
+  pub fn set_max_values(&mut self, max_values: Option<usize>) {
+    self.max_values = max_values;
+  }
+
+  /// Sets the responses tried for the requests labelled `label`, such as
+  /// `u32` or `string`. An empty list restores the default samples.
+  pub fn set_samples<I, S>(&mut self, label: &str, samples: I)
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    let samples: Vec<String> = samples.into_iter().map(Into::into).collect();
+    if samples.is_empty() {
+      self.samples.remove(label);
+    } else {
+      self.samples.insert(label.to_string(), samples);
+    }
+  }
+
+  /// Returns every distinct value of `T` within the limits, in the order
+  /// found.
+  ///
+  /// Answers which the type rejects with a Serde error are skipped, as are
+  /// enums beyond the depth limit, while other errors are returned. Values
+  /// are compared with each one found before, which takes time quadratic in
+  /// the number of values.
+  pub fn values<T: DeserializeOwned + PartialEq>(&self) -> Result<Vec<T>> {
+    let mut values: Vec<T> = Vec::new();
+    let mut choices = Vec::new();
+    loop {
+      let prompt = EnumeratePrompt {
+        enumerator: self,
+        choices: &mut choices,
+        pos: 0,
+        counts: Vec::new(),
+      };
+      match from_bare_prompt::<T, _>(prompt) {
+        Ok(value) => {
+          if !values.contains(&value) {
+            values.push(value);
+          }
+        }
+        Err(Error::SerdeError(_, _)) => {}
+        Err(e) => return Err(e),
+      }
+      if Some(values.len()) == self.max_values || !advance(&mut choices) {
+        return Ok(values);
+      }
+    }
+  }
+}
+
+impl Default for Enumerator {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Moves on to the next combination of choices, returning false once all of
+/// them have been tried.
+fn advance(choices: &mut Vec<(usize, usize)>) -> bool {
+  while let Some((chosen, count)) = choices.last_mut() {
+    if *chosen + 1 < *count {
+      *chosen += 1;
+      return true;
+    }
+    choices.pop();
+  }
+  false
+}
+
+/// Returns the error which prunes the search at an enum beyond the depth
+/// limit, which is a Serde error so that `values` skips it.
+fn too_deep() -> Error {
+  Error::SerdeError("Enum beyond the depth limit".to_string(), None)
+}
+
+/// Prompt which replays the choices made so far and makes the first choice
+/// for each new request.
+struct EnumeratePrompt<'a> {
+  enumerator: &'a Enumerator,
+  // The choice made for each request and the number of options, in order
+  choices: &'a mut Vec<(usize, usize)>,
+  pos: usize,
+  // The number of elements added to each open scope
+  counts: Vec<usize>,
+}
+
+impl<'a> EnumeratePrompt<'a> {
+  fn is_deep(&self) -> bool {
+    self.counts.len() >= self.enumerator.max_depth
+  }
+
+  fn choose(&mut self, count: usize) -> usize {
+    let count = if self.is_deep() { 1 } else { count };
+    if self.pos == self.choices.len() {
+      self.choices.push((0, count));
+    }
+    self.pos += 1;
+    self.choices[self.pos - 1].0
+  }
+
+  /// Chooses whether to add another element to the collection `up` scopes
+  /// above the current one.
+  fn add_element(&mut self, up: usize) -> String {
+    let n = self.counts.len();
+    let full = match self.counts.get(n.wrapping_sub(up + 1)) {
+      Some(added) => *added >= self.enumerator.max_length,
+      None => true,
+    };
+    if full || self.choose(2) == 0 {
+      "no".to_string()
+    } else {
+      self.counts[n - up - 1] += 1;
+      "yes".to_string()
+    }
+  }
+}
+
+impl<'a> PromptResponder for EnumeratePrompt<'a> {
+  fn begin_scope(&mut self, _name: &str, _size: Option<usize>) -> Result<()> {
+    self.counts.push(0);
+    Ok(())
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    self.counts.pop();
+    Ok(())
+  }
+
+  fn respond(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    _response: &str,
+  ) -> Result<()> {
+    // Enums of a single variant don't request it
+    if kind == RequestKind::Synthetic && prompt == "variant" && self.is_deep() {
+      return Err(too_deep());
+    }
+    Ok(())
+  }
+}
+
+impl<'a> PromptRequester for EnumeratePrompt<'a> {
+  fn is_interactive(&self) -> bool {
+    false
+  }
+
+  fn request(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    variants: &'static [&'static str],
+  ) -> Result<String> {
+    if prompt == "variant" && self.is_deep() {
+      return Err(too_deep());
+    }
+    Ok(match (kind, prompt) {
+      (RequestKind::Question, "Add element?")
+      | (RequestKind::Question, "Add entry?") => self.add_element(1),
+      (RequestKind::Question, "Add byte?") => self.add_element(0),
+      (RequestKind::Question, "Some value?") => {
+        ["no", "yes"][self.choose(2)].to_string()
+      }
+      (RequestKind::Question, _) | (_, "bool") | (_, "variant")
+        if !variants.is_empty() =>
+      {
+        variants[self.choose(variants.len())].to_string()
+      }
+      (_, label) => {
+        let samples = match self.enumerator.samples.get(label) {
+          Some(samples) => samples.clone(),
+          None => default_samples(label),
+        };
+        samples[self.choose(samples.len())].clone()
+      }
+    })
+  }
+
+  fn report(&mut self, _kind: ReportKind, _msg: &str) -> Result<()> {
+    Ok(())
+  }
+}
@@ -1,3 +1,4 @@
+use crate::path::ScopePath;
 use std::fmt::{self, Display};
 
 use serde::{de, ser};
@@ -6,7 +7,8 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 /// This type represents actions a user may take while interacting with the
 /// deserialiser.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum UserAction {
   /// Cancel deserialising.
   Cancel,
@@ -14,38 +16,68 @@ pub enum UserAction {
   Restart(usize),
   /// Undo the last `n` responses to the deserialiser.
   Undo(usize),
+  /// Redo the last `n` undone responses to the deserialiser.
+  Redo(usize),
+  /// Revise the responses given within the scope matching path `p`.
+  Revise(String),
+  /// Undo to the start of the current scope.
+  Up,
+  /// Go back to the start of the scope matching path `p`.
+  Back(String),
+  /// Show the responses given so far, or those within the scope matching
+  /// path `p`.
+  Show(Option<String>),
+  /// List the responses given so far with their positions.
+  History,
 }
 
 /// This type represents errors that may occur.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Error {
-  SerdeError(String),
+  /// A Serde error, with the path of the scope in which it was raised if
+  /// known.
+  SerdeError(String, Option<ScopePath>),
   IoError(String),
   UserAction(UserAction),
   BadResponse,
   CannotReplay,
+  ReplayMismatch,
+  SessionMismatch,
+  OutOfResponses,
+  TranscriptError(String),
+  FlatError(String),
 }
 
 impl ser::Error for Error {
   fn custom<T: Display>(msg: T) -> Self {
-    Error::SerdeError(msg.to_string())
+    Error::SerdeError(msg.to_string(), None)
   }
 }
 
 impl de::Error for Error {
   fn custom<T: Display>(msg: T) -> Self {
-    Error::SerdeError(msg.to_string())
+    Error::SerdeError(msg.to_string(), None)
   }
 }
 
 impl Display for Error {
   fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
     match self {
-      Error::SerdeError(msg) => write!(fmt, "Serde: {}", msg),
+      Error::SerdeError(msg, None) => write!(fmt, "Serde: {}", msg),
+      Error::SerdeError(msg, Some(path)) => {
+        write!(fmt, "Serde: {} (at {})", msg, path)
+      }
       Error::IoError(msg) => write!(fmt, "I/O: {}", msg),
       Error::UserAction(action) => write!(fmt, "UserAction: {:?}", action),
       Error::BadResponse => write!(fmt, "Bad Response"),
       Error::CannotReplay => write!(fmt, "Cannot Replay"),
+      Error::ReplayMismatch => write!(fmt, "Replay Mismatch"),
+      Error::SessionMismatch => {
+        write!(fmt, "Session was saved for a different type")
+      }
+      Error::OutOfResponses => write!(fmt, "Out of Responses"),
+      Error::TranscriptError(msg) => write!(fmt, "Transcript: {}", msg),
+      Error::FlatError(msg) => write!(fmt, "Flat: {}", msg),
     }
   }
 }
@@ -0,0 +1,434 @@
+use crate::document::{DocumentPrompt, Node};
+use crate::error::{Error, Result};
+use crate::prompt::{
+  PromptRequester, PromptResponder, ReportKind, RequestKind,
+};
+
+/// Splits a flat key into its path, normalising indices.
+fn split_key<'a, I: Iterator<Item = &'a str>>(parts: I) -> Vec<String> {
+  parts
+    .map(|part| match part.parse::<usize>() {
+      Ok(i) => i.to_string(),
+      Err(_) => part.to_string(),
+    })
+    .collect()
+}
+
+/// A segment of a path within a tree.
+#[derive(Clone, PartialEq)]
+enum Segment {
+  Index(usize),
+  Key(String),
+}
+
+impl Segment {
+  /// Takes a segment which parses as a number to be an index.
+  fn new(s: &str) -> Self {
+    match s.parse::<usize>() {
+      Ok(i) => Segment::Index(i),
+      Err(_) => Segment::Key(s.to_string()),
+    }
+  }
+}
+
+#[derive(Default)]
+struct Tree {
+  value: Option<Node>,
+  children: Vec<(Segment, Tree)>,
+}
+
+impl Tree {
+  fn insert(&mut self, path: &[Segment], value: Node) {
+    match path.split_first() {
+      None => self.value = Some(value),
+      Some((key, rest)) => {
+        let i = match self.children.iter().position(|(k, _)| k == key) {
+          Some(i) => i,
+          None => {
+            self.children.push((key.clone(), Tree::default()));
+            self.children.len() - 1
+          }
+        };
+        self.children[i].1.insert(rest, value);
+      }
+    }
+  }
+
+  /// Converts the tree into a document, in which children with only indices
+  /// for keys become seqs.
+  fn into_node(self) -> Node {
+    if self.children.is_empty() {
+      return self.value.unwrap_or(Node::Null);
+    }
+    let mut indices = Vec::new();
+    for (k, _) in self.children.iter() {
+      match k {
+        Segment::Index(i) => indices.push(*i),
+        Segment::Key(_) => break,
+      }
+    }
+    if indices.len() == self.children.len() {
+      let mut children: Vec<(usize, Tree)> = indices
+        .into_iter()
+        .zip(self.children.into_iter().map(|(_, t)| t))
+        .collect();
+      children.sort_by_key(|(i, _)| *i);
+      Node::Seq(children.into_iter().map(|(_, t)| t.into_node()).collect())
+    } else {
+      Node::Map(
+        self
+          .children
+          .into_iter()
+          .map(|(k, t)| {
+            let k = match k {
+              Segment::Index(i) => i.to_string(),
+              Segment::Key(k) => k,
+            };
+            (Node::Value(k), t.into_node())
+          })
+          .collect(),
+      )
+    }
+  }
+}
+
+fn needs_quotes(s: &str, is_key: bool) -> bool {
+  s.is_empty()
+    // Unquoted numeric keys are indices, and these values empty collections
+    || is_key && s.bytes().all(|b| b.is_ascii_digit())
+    || !is_key && (s == "[]" || s == "{}")
+    || s.starts_with(char::is_whitespace)
+    || s.ends_with(char::is_whitespace)
+    || s.starts_with('"')
+    || s.contains(|c: char| c.is_control())
+    || is_key && s.contains(['.', '=', '"'])
+}
+
+/// Quotes `s` if it couldn't otherwise be parsed back unchanged.
+fn quote(s: &str, is_key: bool) -> String {
+  if !needs_quotes(s, is_key) {
+    return s.to_string();
+  }
+  let mut res = String::with_capacity(s.len() + 2);
+  res.push('"');
+  for c in s.chars() {
+    match c {
+      '"' => res.push_str("\\\""),
+      '\\' => res.push_str("\\\\"),
+      '\n' => res.push_str("\\n"),
+      '\r' => res.push_str("\\r"),
+      '\t' => res.push_str("\\t"),
+      c => res.push(c),
+    }
+  }
+  res.push('"');
+  res
+}
+
+/// Parses a quoted string at the start of `s`, returning it and the rest.
+fn unquote(s: &str) -> Option<(String, &str)> {
+  let mut res = String::new();
+  let mut chars = s.strip_prefix('"')?.char_indices();
+  while let Some((_, c)) = chars.next() {
+    match c {
+      '"' => return Some((res, chars.as_str())),
+      '\\' => match chars.next()?.1 {
+        '"' => res.push('"'),
+        '\\' => res.push('\\'),
+        'n' => res.push('\n'),
+        'r' => res.push('\r'),
+        't' => res.push('\t'),
+        _ => return None,
+      },
+      c => res.push(c),
+    }
+  }
+  None
+}
+
+/// Appends a line for each value within `node` to `out`.
+pub(crate) fn write_flat(
+  node: &Node,
+  path: &mut Vec<String>,
+  out: &mut String,
+) {
+  let mut write_line = |value: &str| {
+    out.push_str(&path.join("."));
+    out.push('=');
+    out.push_str(value);
+    out.push('\n');
+  };
+  match node {
+    // Empty collections are written so that they aren't lost
+    Node::Seq(elements) if elements.is_empty() => write_line("[]"),
+    Node::Map(entries) if entries.is_empty() => write_line("{}"),
+    Node::Null => write_line(""),
+    Node::Value(value) => write_line(&quote(value, false)),
+    Node::Seq(elements) => {
+      for (i, element) in elements.iter().enumerate() {
+        path.push(i.to_string());
+        write_flat(element, path, out);
+        path.pop();
+      }
+    }
+    Node::Map(entries) => {
+      for (key, value) in entries {
+        let key = match key {
+          Node::Value(key) => key.clone(),
+          // Keys which aren't plain values can't be written flat
+          _ => continue,
+        };
+        path.push(quote(&key, true));
+        write_flat(value, path, out);
+        path.pop();
+      }
+    }
+  }
+}
+
+fn parse_line(line: &str) -> Option<(Vec<Segment>, Node)> {
+  let mut path = Vec::new();
+  let mut rest = line;
+  if !rest.starts_with('=') {
+    loop {
+      // Quoted segments are always keys, even if numeric
+      let (segment, tail) = if rest.starts_with('"') {
+        let (key, tail) = unquote(rest)?;
+        (Segment::Key(key), tail)
+      } else {
+        let end = rest.find(['.', '='])?;
+        (Segment::new(&rest[..end]), &rest[end..])
+      };
+      path.push(segment);
+      match tail.strip_prefix('.') {
+        Some(tail) => rest = tail,
+        None => {
+          rest = tail;
+          break;
+        }
+      }
+    }
+  }
+  let value = rest.strip_prefix('=')?;
+  let value = match value {
+    "" => Node::Null,
+    "[]" => Node::Seq(Vec::new()),
+    "{}" => Node::Map(Vec::new()),
+    value if value.starts_with('"') => match unquote(value)? {
+      (value, "") => Node::Value(value),
+      _ => return None,
+    },
+    value => Node::Value(value.to_string()),
+  };
+  Some((path, value))
+}
+
+/// Parses lines of the form written by `write_flat` into a document.
+pub(crate) fn parse_flat(s: &str) -> Result<Node> {
+  let mut tree = Tree::default();
+  for (i, line) in s.lines().enumerate() {
+    if line.trim().is_empty() || line.starts_with('#') {
+      continue;
+    }
+    match parse_line(line) {
+      Some((path, value)) => tree.insert(&path, value),
+      None => {
+        return Err(Error::FlatError(format!("line {}: bad syntax", i + 1)));
+      }
+    }
+  }
+  Ok(tree.into_node())
+}
+
+/// A key given to a keyed prompt.
+struct Key {
+  path: Vec<String>,
+  name: String,
+}
+
+/// Prompt decorator which answers requests from flat keys.
+struct KeyedPrompt<P> {
+  document: DocumentPrompt<P>,
+  keys: Vec<Key>,
+  unknown: Vec<String>,
+  noun: &'static str,
+}
+
+impl<P: PromptRequester> KeyedPrompt<P> {
+  fn new(
+    keys: Vec<(Key, String)>,
+    unknown: Vec<String>,
+    ignore_case: bool,
+    noun: &'static str,
+    inner: P,
+  ) -> Self {
+    let mut tree = Tree::default();
+    for (key, value) in keys.iter() {
+      let path: Vec<Segment> =
+        key.path.iter().map(|k| Segment::new(k)).collect();
+      tree.insert(&path, Node::Value(value.clone()));
+    }
+    KeyedPrompt {
+      document: DocumentPrompt::from_node(tree.into_node(), ignore_case, inner),
+      keys: keys.into_iter().map(|(key, _)| key).collect(),
+      unknown,
+      noun,
+    }
+  }
+
+  fn report_unused(&mut self) -> Result<()> {
+    let mut names = self.unknown.clone();
+    for key in self.keys.iter() {
+      if !self.document.is_used(&key.path) {
+        names.push(key.name.clone());
+      }
+    }
+    for name in names {
+      let msg = format!("Unused {}: {}", self.noun, name);
+      self.document.report(ReportKind::Help, &msg)?;
+    }
+    Ok(())
+  }
+}
+
+macro_rules! keyed_prompt_impls {
+  ($tname:ident) => {
+    impl<P: PromptRequester> PromptResponder for $tname<P> {
+      fn begin_scope(&mut self, name: &str, size: Option<usize>) -> Result<()> {
+        self.keyed.document.begin_scope(name, size)
+      }
+
+      fn end_scope(&mut self) -> Result<()> {
+        self.keyed.document.end_scope()
+      }
+
+      fn respond(
+        &mut self,
+        kind: RequestKind,
+        prompt: &str,
+        response: &str,
+      ) -> Result<()> {
+        self.keyed.document.respond(kind, prompt, response)
+      }
+    }
+
+    impl<P: PromptRequester> PromptRequester for $tname<P> {
+      fn is_interactive(&self) -> bool {
+        self.keyed.document.is_interactive()
+      }
+
+      fn request(
+        &mut self,
+        kind: RequestKind,
+        prompt: &str,
+        variants: &'static [&'static str],
+      ) -> Result<String> {
+        self.keyed.document.request(kind, prompt, variants)
+      }
+
+      fn report(&mut self, kind: ReportKind, msg: &str) -> Result<()> {
+        self.keyed.document.report(kind, msg)
+      }
+    }
+
+    impl<P: PromptRequester> $tname<P> {
+      /// Reports the keys which haven't been used to answer a request, or
+      /// which weren't understood, to the inner prompt.
+      pub fn report_unused(&mut self) -> Result<()> {
+        self.keyed.report_unused()
+      }
+    }
+  };
+}
+
+/// Prompt decorator which answers requests from environment variables.
+///
+/// Variables are named after the path of a value within the document
+/// answered by a `DocumentPrompt`, with its keys and indices separated by
+/// double underscores and a prefix added. For example, with the prefix `APP`
+/// the variable `APP__PARENT__NAME` answers for the field `name` of the field
+/// `parent` of the value being deserialised, and `APP__CHILDREN__0__NAME` for
+/// the field `name` of the first element of the field `children`. Keys are
+/// matched regardless of case.
+///
+/// Requests which aren't answered by a variable are passed to the inner
+/// prompt.
+pub struct EnvPrompt<P> {
+  keyed: KeyedPrompt<P>,
+}
+
+impl<P: PromptRequester> EnvPrompt<P> {
+  /// Creates a prompt answering from the variables of the environment
+  /// beginning with `prefix`.
+  pub fn new(prefix: &str, inner: P) -> Self {
+    EnvPrompt::from_vars(prefix, std::env::vars(), inner)
+  }
+
+  /// Creates a prompt answering from the given variables beginning with
+  /// `prefix`.
+  pub fn from_vars<I: IntoIterator<Item = (String, String)>>(
+    prefix: &str,
+    vars: I,
+    inner: P,
+  ) -> Self {
+    let prefix = format!("{}__", prefix);
+    let mut keys = Vec::new();
+    for (name, value) in vars {
+      if let Some(key) = name.strip_prefix(&prefix) {
+        let path = split_key(key.split("__"));
+        keys.push((Key { path, name }, value));
+      }
+    }
+    EnvPrompt {
+      keyed: KeyedPrompt::new(
+        keys,
+        Vec::new(),
+        true,
+        "environment variable",
+        inner,
+      ),
+    }
+  }
+}
+
+keyed_prompt_impls!(EnvPrompt);
+
+/// Prompt decorator which answers requests from command-line arguments.
+///
+/// Arguments take the form `--<path>=<value>`, where the path is that of a
+/// value within the document answered by a `DocumentPrompt` with its keys and
+/// indices separated by full stops. For example, `--parent.name=Bob` answers
+/// for the field `name` of the field `parent` of the value being deserialised,
+/// and `--children.0.name=Alice` for the field `name` of the first element of
+/// the field `children`.
+///
+/// Requests which aren't answered by an argument are passed to the inner
+/// prompt. Arguments of other forms are reported as unused.
+pub struct ArgsPrompt<P> {
+  keyed: KeyedPrompt<P>,
+}
+
+impl<P: PromptRequester> ArgsPrompt<P> {
+  /// Creates a prompt answering from the given arguments, which shouldn't
+  /// include the program name.
+  pub fn new<I: IntoIterator<Item = String>>(args: I, inner: P) -> Self {
+    let mut keys = Vec::new();
+    let mut unknown = Vec::new();
+    for arg in args {
+      let pair = arg.strip_prefix("--").and_then(|s| s.split_once('='));
+      match pair {
+        Some((key, value)) if !key.is_empty() => {
+          let path = split_key(key.split('.'));
+          let value = value.to_string();
+          keys.push((Key { path, name: arg }, value));
+        }
+        _ => unknown.push(arg),
+      }
+    }
+    ArgsPrompt {
+      keyed: KeyedPrompt::new(keys, unknown, false, "argument", inner),
+    }
+  }
+}
+
+keyed_prompt_impls!(ArgsPrompt);
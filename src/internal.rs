@@ -1,9 +1,10 @@
 use crate::error::Result;
+use crate::path::ScopePath;
 use crate::prompt::{
   PromptRequester, PromptResponder, ReportKind, RequestKind,
 };
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum ScopeLimit {
   Explicit,
   Implicit,
@@ -14,6 +15,9 @@ pub(crate) struct ScopeEntry(ScopeLimit, u32);
 pub(crate) struct InternalPrompt<P: PromptResponder> {
   inner: P,
   scopes: Vec<ScopeEntry>,
+  path: ScopePath,
+  // The limits of the scopes begun, in order, while recording them
+  limits: Option<Vec<ScopeLimit>>,
 }
 
 impl<P: PromptResponder> InternalPrompt<P> {
@@ -21,15 +25,33 @@ impl<P: PromptResponder> InternalPrompt<P> {
     InternalPrompt {
       inner,
       scopes: Vec::new(),
+      path: ScopePath::new(),
+      limits: None,
     }
   }
 
+  /// Starts recording the limit of each scope begun from now on, including
+  /// those which the inner prompt fails to begin.
+  pub fn record_limits(&mut self) {
+    self.limits = Some(Vec::new());
+  }
+
+  /// Returns the limits recorded so far.
+  pub fn take_limits(&mut self) -> Vec<ScopeLimit> {
+    self.limits.as_mut().map(std::mem::take).unwrap_or_default()
+  }
+
+  pub fn path(&self) -> &ScopePath {
+    &self.path
+  }
+
   pub fn cleanup(&mut self) -> Result<()> {
     while let Some(ScopeEntry(_, n)) = self.scopes.last_mut() {
       *n -= 1;
       if *n == 0 {
         self.scopes.pop();
       }
+      self.path.pop();
       self.inner.end_scope()?;
     }
     Ok(())
@@ -41,7 +63,11 @@ impl<P: PromptResponder> InternalPrompt<P> {
     size: Option<usize>,
     limit: ScopeLimit,
   ) -> Result<()> {
+    if let Some(limits) = &mut self.limits {
+      limits.push(limit);
+    }
     self.inner.begin_scope(name, size)?;
+    self.path.push(name);
     match self.scopes.last_mut() {
       Some(ScopeEntry(lim, n)) if limit == *lim => *n += 1,
       _ => self.scopes.push(ScopeEntry(limit, 1)),
@@ -56,6 +82,7 @@ impl<P: PromptResponder> InternalPrompt<P> {
       if *n == 0 {
         self.scopes.pop();
       }
+      self.path.pop();
       self.inner.end_scope()?;
     }
     Ok(())
@@ -74,6 +101,7 @@ impl<P: PromptResponder> PromptResponder for InternalPrompt<P> {
   }
 
   fn end_scope(&mut self) -> Result<()> {
+    self.path.pop();
     self.inner.end_scope()?;
     match self.scopes.last_mut() {
       Some(ScopeEntry(ScopeLimit::Explicit, n)) => {
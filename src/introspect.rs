@@ -0,0 +1,479 @@
+use crate::de::Deserializer;
+use crate::error::Result;
+use crate::internal::ScopeLimit;
+use crate::prompt::{
+  PromptRequester, PromptResponder, ReportKind, RequestKind,
+};
+use crate::shape::{Explorer, Run};
+use serde::de::DeserializeOwned;
+
+const PRIMITIVES: &[&str] = &[
+  "bool",
+  "u8",
+  "u16",
+  "u32",
+  "u64",
+  "u128",
+  "i8",
+  "i16",
+  "i32",
+  "i64",
+  "i128",
+  "f32",
+  "f64",
+  "char",
+  "string",
+  "identifier",
+];
+
+/// The shape of a type, as discovered by `describe`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+  /// A primitive requested with the given label, such as `u32` or `string`.
+  Primitive(&'static str),
+  /// A byte buffer.
+  Bytes,
+  /// The unit type, or a unit variant.
+  Unit,
+  /// A unit struct with the given name.
+  UnitStruct(String),
+  /// A newtype struct with the given name.
+  Newtype(String, Box<Shape>),
+  /// A struct, or a struct variant, with the given name and fields.
+  Struct(String, Vec<(String, Shape)>),
+  /// A tuple struct, or a tuple variant, with the given name and elements.
+  /// Tuples are named `tuple`.
+  Tuple(String, Vec<Shape>),
+  /// An option.
+  Option(Box<Shape>),
+  /// A seq with elements of the given shape.
+  Seq(Box<Shape>),
+  /// A map with keys and values of the given shapes.
+  Map(Box<Shape>, Box<Shape>),
+  /// An enum with the given name and variants.
+  Enum(String, Vec<(String, Shape)>),
+  /// A type of the given name nested within itself, which isn't described
+  /// again.
+  Recursive(String),
+  /// A shape which couldn't be discovered.
+  Unknown,
+}
+
+impl Shape {
+  /// Returns true if the type contains itself.
+  pub fn is_recursive(&self) -> bool {
+    match self {
+      Shape::Recursive(_) => true,
+      Shape::Newtype(_, s) | Shape::Option(s) | Shape::Seq(s) => {
+        s.is_recursive()
+      }
+      Shape::Map(k, v) => k.is_recursive() || v.is_recursive(),
+      Shape::Struct(_, fields) | Shape::Enum(_, fields) => {
+        fields.iter().any(|(_, s)| s.is_recursive())
+      }
+      Shape::Tuple(_, elements) => elements.iter().any(Shape::is_recursive),
+      _ => false,
+    }
+  }
+
+  /// Returns the name of the type, if it's a named type which may contain
+  /// other types.
+  fn type_name(&self) -> Option<&str> {
+    match self {
+      Shape::Newtype(name, _)
+      | Shape::Struct(name, _)
+      | Shape::Enum(name, _) => Some(name),
+      Shape::Tuple(name, _) if name != "tuple" => Some(name),
+      _ => None,
+    }
+  }
+
+  /// Fills in the parts of this shape which are unknown from `other`.
+  fn merge(&mut self, other: Shape) {
+    match (self, other) {
+      (s @ Shape::Unknown, other) => *s = other,
+      (Shape::Newtype(_, a), Shape::Newtype(_, b))
+      | (Shape::Option(a), Shape::Option(b))
+      | (Shape::Seq(a), Shape::Seq(b)) => a.merge(*b),
+      (Shape::Map(ak, av), Shape::Map(bk, bv)) => {
+        ak.merge(*bk);
+        av.merge(*bv);
+      }
+      // Fields after a failure are missing, so they're merged by name
+      (Shape::Struct(_, a), Shape::Struct(_, b)) => {
+        for (name, b) in b {
+          match a.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, a)) => a.merge(b),
+            None => a.push((name, b)),
+          }
+        }
+      }
+      (Shape::Enum(_, a), Shape::Enum(_, b)) => {
+        for ((_, a), (_, b)) in a.iter_mut().zip(b) {
+          a.merge(b);
+        }
+      }
+      (Shape::Tuple(_, a), Shape::Tuple(_, b)) => {
+        for (a, b) in a.iter_mut().zip(b) {
+          a.merge(b);
+        }
+      }
+      _ => {}
+    }
+  }
+
+  /// Returns true if the two shapes may be of the same type, taking the parts
+  /// which are unknown to match anything.
+  fn matches(&self, other: &Shape) -> bool {
+    match (self, other) {
+      (Shape::Unknown, _) | (_, Shape::Unknown) => true,
+      (Shape::Newtype(a, s), Shape::Newtype(b, t)) => a == b && s.matches(t),
+      (Shape::Option(s), Shape::Option(t)) | (Shape::Seq(s), Shape::Seq(t)) => {
+        s.matches(t)
+      }
+      (Shape::Map(sk, sv), Shape::Map(tk, tv)) => {
+        sk.matches(tk) && sv.matches(tv)
+      }
+      (Shape::Struct(a, s), Shape::Struct(b, t)) => {
+        a == b
+          && s.iter().all(|(n, s)| {
+            t.iter().filter(|(m, _)| m == n).all(|(_, t)| s.matches(t))
+          })
+      }
+      (Shape::Enum(a, s), Shape::Enum(b, t)) => {
+        a == b
+          && s.len() == t.len()
+          && s
+            .iter()
+            .zip(t)
+            .all(|((n, s), (m, t))| n == m && s.matches(t))
+      }
+      (Shape::Tuple(a, s), Shape::Tuple(b, t)) => {
+        a == b
+          && s.len() == t.len()
+          && s.iter().zip(t).all(|(s, t)| s.matches(t))
+      }
+      (s, t) => s == t,
+    }
+  }
+
+  /// Returns this shape with each type nested within a type of the same name
+  /// and a matching shape replaced by `Shape::Recursive`.
+  ///
+  /// Types nested within types of the same name but a different shape, such
+  /// as generic types with other parameters, are kept.
+  fn fold<'a>(&'a self, outer: &mut Vec<&'a Shape>) -> Shape {
+    let name = self.type_name();
+    if let Some(name) = name {
+      let nested = outer
+        .iter()
+        .any(|o| o.type_name() == Some(name) && o.matches(self));
+      if nested {
+        return Shape::Recursive(name.to_string());
+      }
+      outer.push(self);
+    }
+    let folded = match self {
+      Shape::Newtype(n, s) => {
+        Shape::Newtype(n.clone(), Box::new(s.fold(outer)))
+      }
+      Shape::Option(s) => Shape::Option(Box::new(s.fold(outer))),
+      Shape::Seq(s) => Shape::Seq(Box::new(s.fold(outer))),
+      Shape::Map(k, v) => {
+        Shape::Map(Box::new(k.fold(outer)), Box::new(v.fold(outer)))
+      }
+      Shape::Struct(n, fields) => {
+        Shape::Struct(n.clone(), fold_named(fields, outer))
+      }
+      Shape::Enum(n, variants) => {
+        Shape::Enum(n.clone(), fold_named(variants, outer))
+      }
+      Shape::Tuple(n, elements) => Shape::Tuple(
+        n.clone(),
+        elements.iter().map(|s| s.fold(outer)).collect(),
+      ),
+      s => s.clone(),
+    };
+    if name.is_some() {
+      outer.pop();
+    }
+    folded
+  }
+}
+
+fn fold_named<'a>(
+  shapes: &'a [(String, Shape)],
+  outer: &mut Vec<&'a Shape>,
+) -> Vec<(String, Shape)> {
+  shapes
+    .iter()
+    .map(|(n, s)| (n.clone(), s.fold(outer)))
+    .collect()
+}
+
+/// Describes the shape of `T` by deserialising it several times with a
+/// `ProbePrompt`, following each answer to each question and variant request.
+pub(crate) fn describe<T: DeserializeOwned>() -> Result<Shape> {
+  let mut shape = Shape::Unknown;
+  let mut error = None;
+  let mut explorer = Explorer::new();
+  while let Some(run) = explorer.next_run() {
+    let mut prompt = ProbePrompt::new(run);
+    let (res, limits) = {
+      let mut de = Deserializer::from_prompt(&mut prompt);
+      de.record_scope_limits();
+      let res = T::deserialize(&mut de);
+      (res, de.take_scope_limits())
+    };
+    shape.merge(value_shape(prompt.finish(limits).first()));
+    if let Err(e) = res {
+      error.get_or_insert(e);
+    }
+  }
+  match (shape, error) {
+    (Shape::Unknown, Some(e)) => Err(e),
+    (shape, _) => Ok(shape.fold(&mut Vec::new())),
+  }
+}
+
+/// A request or scope met while probing a type. Scopes are implicit if the
+/// deserialiser ends them along with the value within them, as it does for
+/// newtype structs, fields and elements.
+enum Event {
+  Request(RequestKind, String, &'static [&'static str], String),
+  Scope(String, Option<usize>, ScopeLimit, Vec<Event>),
+}
+
+/// Prompt which gives minimal responses while recording the requests and
+/// scopes met.
+struct ProbePrompt<'a> {
+  run: Run<'a>,
+  // The scopes open, with the events recorded within each of them so far
+  open: Vec<(String, Option<usize>, Vec<Event>)>,
+  events: Vec<Event>,
+}
+
+impl<'a> ProbePrompt<'a> {
+  fn new(run: Run<'a>) -> Self {
+    ProbePrompt {
+      run,
+      open: Vec::new(),
+      events: Vec::new(),
+    }
+  }
+
+  fn push(&mut self, event: Event) {
+    match self.open.last_mut() {
+      Some((_, _, events)) => events.push(event),
+      None => self.events.push(event),
+    }
+  }
+
+  fn close(&mut self) -> bool {
+    match self.open.pop() {
+      Some((name, size, events)) => {
+        // The limit is filled in once the deserialiser has recorded it
+        let event = Event::Scope(name, size, ScopeLimit::Explicit, events);
+        self.push(event);
+        true
+      }
+      None => false,
+    }
+  }
+
+  /// Returns the events recorded, closing any scopes left open by a failure
+  /// and filling in the `limits` of the scopes in the order they were begun.
+  fn finish(mut self, limits: Vec<ScopeLimit>) -> Vec<Event> {
+    while self.close() {}
+    set_limits(&mut self.events, &mut limits.into_iter());
+    self.events
+  }
+}
+
+fn set_limits(
+  events: &mut [Event],
+  limits: &mut impl Iterator<Item = ScopeLimit>,
+) {
+  for event in events {
+    if let Event::Scope(_, _, limit, events) = event {
+      if let Some(l) = limits.next() {
+        *limit = l;
+      }
+      set_limits(events, limits);
+    }
+  }
+}
+
+impl<'a> PromptResponder for ProbePrompt<'a> {
+  fn begin_scope(&mut self, name: &str, size: Option<usize>) -> Result<()> {
+    self.open.push((name.to_string(), size, Vec::new()));
+    self.run.begin_scope(name)
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    self.close();
+    self.run.end_scope();
+    Ok(())
+  }
+
+  fn respond(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    response: &str,
+  ) -> Result<()> {
+    let event =
+      Event::Request(kind, prompt.to_string(), &[], response.to_string());
+    self.push(event);
+    Ok(())
+  }
+}
+
+impl<'a> PromptRequester for ProbePrompt<'a> {
+  fn is_interactive(&self) -> bool {
+    false
+  }
+
+  fn request(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    variants: &'static [&'static str],
+  ) -> Result<String> {
+    let response = self.run.answer(kind, prompt, variants);
+    let event =
+      Event::Request(kind, prompt.to_string(), variants, response.clone());
+    self.push(event);
+    Ok(response)
+  }
+
+  fn report(&mut self, _kind: ReportKind, _msg: &str) -> Result<()> {
+    Ok(())
+  }
+}
+
+/// Returns the shape of the value given by `event`.
+fn value_shape(event: Option<&Event>) -> Shape {
+  match event {
+    Some(Event::Request(_, label, _, _)) if label == "unit" => Shape::Unit,
+    Some(Event::Request(_, label, _, _)) => {
+      match PRIMITIVES.iter().find(|p| *p == label) {
+        Some(p) => Shape::Primitive(p),
+        None => Shape::Unknown,
+      }
+    }
+    Some(Event::Scope(name, size, limit, events)) => {
+      match (name.as_str(), size) {
+        ("option", None) => Shape::Option(Box::new(value_shape(events.get(1)))),
+        ("bytes", None) => Shape::Bytes,
+        ("seq", None) => {
+          Shape::Seq(Box::new(value_shape(first_entry(events).get(1))))
+        }
+        ("map", None) => {
+          let entry = first_entry(events);
+          Shape::Map(
+            Box::new(value_shape(entry.get(1))),
+            Box::new(value_shape(entry.get(2))),
+          )
+        }
+        (name, None) => enum_shape(name, events),
+        ("tuple", Some(len)) => {
+          Shape::Tuple(name.clone(), elements(events, *len))
+        }
+        (name, Some(len)) => named_shape(name, *len, *limit, events),
+      }
+    }
+    None => Shape::Unknown,
+  }
+}
+
+/// Returns the events within the first element or entry of a collection,
+/// which begin with the question of whether to add it.
+fn first_entry(events: &[Event]) -> &[Event] {
+  match events.first() {
+    Some(Event::Scope(_, _, _, events)) => events,
+    _ => &[],
+  }
+}
+
+fn elements(events: &[Event], len: usize) -> Vec<Shape> {
+  (1..=len)
+    .map(|i| {
+      let name = format!("[{}/{}]", i, len);
+      events
+        .iter()
+        .find_map(|e| match e {
+          Event::Scope(n, _, _, events) if *n == name => {
+            Some(value_shape(events.first()))
+          }
+          _ => None,
+        })
+        .unwrap_or(Shape::Unknown)
+    })
+    .collect()
+}
+
+fn enum_shape(name: &str, events: &[Event]) -> Shape {
+  let (variants, chosen) = match events.first() {
+    // Enums with a single variant don't request it
+    Some(Event::Request(RequestKind::Synthetic, label, _, chosen))
+      if label == "variant" =>
+    {
+      (vec![chosen.as_str()], chosen)
+    }
+    Some(Event::Request(_, label, variants, chosen)) if label == "variant" => {
+      (variants.to_vec(), chosen)
+    }
+    _ => return Shape::Unknown,
+  };
+  let variants = variants
+    .into_iter()
+    .map(|v| {
+      let s = match v == chosen {
+        true => value_shape(events.get(1)),
+        false => Shape::Unknown,
+      };
+      (v.to_string(), s)
+    })
+    .collect();
+  Shape::Enum(name.to_string(), variants)
+}
+
+/// Returns the shape of a struct, tuple struct, unit struct or newtype struct
+/// from its scope.
+fn named_shape(
+  name: &str,
+  len: usize,
+  limit: ScopeLimit,
+  events: &[Event],
+) -> Shape {
+  let name = name.to_string();
+  match events {
+    // The scope was cut off before anything within it was requested
+    [] if len > 0 => Shape::Unknown,
+    [Event::Request(RequestKind::Synthetic, label, _, _)]
+      if label == "unit" =>
+    {
+      Shape::UnitStruct(name)
+    }
+    // Only newtype structs end along with their value
+    [event] if limit == ScopeLimit::Implicit => {
+      Shape::Newtype(name, Box::new(value_shape(Some(event))))
+    }
+    [Event::Scope(first, _, _, _), ..] if *first == format!("[1/{}]", len) => {
+      Shape::Tuple(name, elements(events, len))
+    }
+    _ => {
+      let fields = events
+        .iter()
+        .filter_map(|e| match e {
+          Event::Scope(field, _, _, events) => {
+            Some((field.clone(), value_shape(events.first())))
+          }
+          _ => None,
+        })
+        .collect();
+      Shape::Struct(name, fields)
+    }
+  }
+}
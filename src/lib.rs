@@ -4,26 +4,61 @@
 //! This crate is a Rust library which uses the Serde serialisation framework
 //! to capture data interactively from users.
 
+// The serialiser and deserialiser impls name the lifetimes of their references
+#![allow(clippy::needless_lifetimes)]
+
 mod error;
 mod internal;
+mod session;
+mod shape;
+mod summary;
 mod u8i8;
 mod util;
 
 /// Serde deserialiser.
 pub mod de;
+/// Differences between values.
+pub mod diff;
+/// Prompt answering requests from documents.
+pub mod document;
+/// Enumerating every value of small types.
+pub mod enumerate;
+/// Prompts answering requests from flat keys.
+pub mod flat;
+/// Describing the shapes of types.
+pub mod introspect;
+/// Rendering values as Markdown and HTML.
+pub mod markup;
+/// Paths identifying scopes within values.
+pub mod path;
 /// Traits and decorators for working with prompts.
 pub mod prompt;
+/// Prompts generating values from random numbers or bytes.
+pub mod random;
 #[cfg(feature = "rustyline")]
 /// Prompt based on the RustyLine crate.
 pub mod rustyline;
+#[cfg(feature = "json_schema")]
+/// JSON Schemas generated from the shapes of types.
+pub mod schema;
 /// Serde serialiser.
 pub mod ser;
 #[cfg(feature = "stdio")]
 /// Prompt based on `std::io`.
 pub mod stdio;
+#[cfg(feature = "testing")]
+/// Helpers for testing code which uses Spaniel.
+pub mod testing;
+/// Reading and writing transcripts of responses.
+pub mod transcript;
 
 pub use error::{Error, Result, UserAction};
+pub use session::{read_responses, write_responses};
 pub use util::{
-  from_bare_prompt, from_bare_prompt_confirm, from_console, from_prompt,
-  from_replay_prompt, to_bare_prompt, to_prompt,
+  describe, diff_values, from_args, from_bare_prompt, from_bare_prompt_confirm,
+  from_console, from_document, from_env, from_flat_str, from_prompt,
+  from_prompt_export, from_prompt_resume, from_prompt_review,
+  from_replay_prompt, from_responses, from_responses_or_prompt,
+  from_transcript_str, to_bare_prompt, to_flat_string, to_prompt, to_responses,
+  to_summary_string, to_test_fixture, to_transcript_string,
 };
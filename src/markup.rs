@@ -0,0 +1,590 @@
+use crate::error::Result;
+use crate::prompt::{PromptResponder, RequestKind};
+
+/// A scope and its contents, as sent to a responder.
+#[derive(Default)]
+struct Scope {
+  name: String,
+  size: Option<usize>,
+  items: Vec<Item>,
+}
+
+enum Item {
+  Scope(Scope),
+  Response(RequestKind, String, String),
+}
+
+/// Value making up a document, with the dialogue removed.
+enum Value {
+  Text(String),
+  Fields(Vec<(String, Value)>),
+  List(Vec<Value>),
+}
+
+/// Returns the label for a scope or prompt name. Where a `CompactPrompt` has
+/// compacted names together, this is the name before the last, which is that
+/// of the innermost field. The scope ending an empty seq or map is passed over
+/// for this.
+fn label(name: &str) -> String {
+  let mut names: Vec<&str> = name.split(" -> ").collect();
+  if names.len() > 2 && is_element(names[names.len() - 1]) {
+    names.pop();
+  }
+  match names.len() {
+    1 => name.to_string(),
+    n => names[n - 2].to_string(),
+  }
+}
+
+/// Returns the last of the names compacted into a scope name.
+fn base(name: &str) -> &str {
+  name.rsplit(" -> ").next().unwrap_or(name)
+}
+
+fn is_element(name: &str) -> bool {
+  name.starts_with('[')
+}
+
+impl Scope {
+  /// Converts the scope into a value, using the sizes of its child scopes to
+  /// tell the fields of structs from other scopes.
+  fn value(&self) -> Value {
+    // An empty seq or map may be compacted into the scope ending it
+    if self.is_end() {
+      return match self.name.rsplit(" -> ").nth(1) {
+        Some("map") => Value::Fields(Vec::new()),
+        _ => Value::List(Vec::new()),
+      };
+    }
+    let mut variant = None;
+    let mut is_none = false;
+    let mut is_entry = false;
+    let mut items: Vec<&Item> = Vec::new();
+    for item in self.items.iter() {
+      match item {
+        Item::Response(RequestKind::Datum, prompt, response)
+          if label(prompt) == "variant" && items.is_empty() =>
+        {
+          variant = Some(response.as_str());
+        }
+        Item::Response(RequestKind::Question, prompt, response) => {
+          let prompt = label(prompt);
+          is_none |= prompt == "Some value?" && response == "no";
+          is_entry |= prompt == "Add entry?" && response == "yes";
+        }
+        Item::Response(RequestKind::Synthetic, _, _) => {}
+        // Scopes ending seqs and maps contain nothing but questions
+        Item::Scope(scope) if scope.is_end() && is_element(&scope.name) => {}
+        item => items.push(item),
+      }
+    }
+    if is_none {
+      return Value::Text("(none)".to_string());
+    }
+    match variant {
+      Some(variant) => {
+        // The fields of struct and tuple variants are within their own scope
+        let value = match items.as_slice() {
+          [Item::Scope(scope)] if label(&scope.name) == variant => {
+            scope.value()
+          }
+          items => Scope::value_of(items, self),
+        };
+        match value {
+          _ if items.is_empty() => Value::Text(variant.to_string()),
+          Value::Text(text) => Value::Text(format!("{}({})", variant, text)),
+          value => Value::Fields(vec![(variant.to_string(), value)]),
+        }
+      }
+      None if is_entry => Value::List(
+        items
+          .iter()
+          .map(|item| match item {
+            Item::Scope(scope) => scope.value(),
+            Item::Response(_, _, response) => Value::Text(response.clone()),
+          })
+          .collect(),
+      ),
+      None => Scope::value_of(&items, self),
+    }
+  }
+
+  /// Converts the contents of `parent` into a value.
+  fn value_of(items: &[&Item], parent: &Scope) -> Value {
+    let is_field = |item: &&Item| match item {
+      Item::Scope(scope) => {
+        // An empty seq or map also has a size of one, for its end question
+        (scope.size == Some(1)
+          && !is_element(&scope.name)
+          && !matches!(base(&scope.name), "seq" | "map"))
+          || (scope.is_end() && !is_element(&scope.name))
+      }
+      Item::Response(_, prompt, _) => prompt.contains(" -> "),
+    };
+    let field = |item: &&Item| match item {
+      Item::Scope(scope) => (label(&scope.name), scope.value()),
+      Item::Response(_, prompt, response) => {
+        (label(prompt), Value::Text(response.clone()))
+      }
+    };
+    match items {
+      [] if base(&parent.name) == "seq" => Value::List(Vec::new()),
+      [] if base(&parent.name) == "map" => Value::Fields(Vec::new()),
+      [] => Value::Text(String::new()),
+      items
+        if items.iter().all(
+          |item| matches!(item, Item::Scope(s) if is_element(&s.name)),
+        ) =>
+      {
+        let values: Vec<Value> = items
+          .iter()
+          .filter_map(|item| match item {
+            Item::Scope(scope) => Some(scope.value()),
+            _ => None,
+          })
+          .collect();
+        // Map entries are lists of their keys and values
+        if base(&parent.name) == "map" {
+          let mut fields = Vec::new();
+          for (i, entry) in values.into_iter().enumerate() {
+            match entry {
+              Value::List(mut kv) if kv.len() == 2 => {
+                let value = kv.pop().unwrap();
+                let key = match kv.pop().unwrap() {
+                  Value::Text(key) => key,
+                  _ => format!("[{}]", i),
+                };
+                fields.push((key, value));
+              }
+              entry => fields.push((format!("[{}]", i), entry)),
+            }
+          }
+          Value::Fields(fields)
+        } else {
+          Value::List(values)
+        }
+      }
+      [item] if !is_field(item) => match item {
+        Item::Scope(scope) => scope.value(),
+        Item::Response(_, _, response) => Value::Text(response.clone()),
+      },
+      items if items.iter().all(is_field) => {
+        Value::Fields(items.iter().map(field).collect())
+      }
+      items if items.iter().all(|i| matches!(i, Item::Response(_, _, _))) => {
+        Value::List(
+          items
+            .iter()
+            .map(|item| match item {
+              Item::Response(_, _, response) => Value::Text(response.clone()),
+              _ => unreachable!(),
+            })
+            .collect(),
+        )
+      }
+      items => Value::Fields(items.iter().map(field).collect()),
+    }
+  }
+
+  /// Returns true if the scope ends a seq or map.
+  fn is_end(&self) -> bool {
+    is_element(base(&self.name))
+      && self.items.iter().any(|item| match item {
+        Item::Response(RequestKind::Question, prompt, response) => {
+          let prompt = label(prompt);
+          response == "no"
+            && (prompt == "Add element?" || prompt == "Add entry?")
+        }
+        _ => false,
+      })
+  }
+}
+
+/// Records the scopes and responses sent to a responder as a tree.
+#[derive(Default)]
+struct Recorder {
+  stack: Vec<Scope>,
+}
+
+impl Recorder {
+  fn begin_scope(&mut self, name: &str, size: Option<usize>) {
+    if self.stack.is_empty() {
+      self.stack.push(Scope::default());
+    }
+    self.stack.push(Scope {
+      name: name.to_string(),
+      size,
+      items: Vec::new(),
+    });
+  }
+
+  fn end_scope(&mut self) {
+    if self.stack.len() > 1 {
+      let scope = self.stack.pop().unwrap();
+      self
+        .stack
+        .last_mut()
+        .unwrap()
+        .items
+        .push(Item::Scope(scope));
+    }
+  }
+
+  fn respond(&mut self, kind: RequestKind, prompt: &str, response: &str) {
+    if self.stack.is_empty() {
+      self.stack.push(Scope::default());
+    }
+    let item = Item::Response(kind, prompt.to_string(), response.to_string());
+    self.stack.last_mut().unwrap().items.push(item);
+  }
+
+  /// Returns the title and value of everything recorded.
+  fn finish(mut self) -> (Option<String>, Value) {
+    while self.stack.len() > 1 {
+      self.end_scope();
+    }
+    let root = self.stack.pop().unwrap_or_default();
+    let title = match root.items.as_slice() {
+      [Item::Scope(scope)] if !is_element(&scope.name) => {
+        Some(label(&scope.name))
+      }
+      _ => None,
+    };
+    (title, root.value())
+  }
+}
+
+/// Returns the rows of a table of `values` and its columns, if all of the
+/// values are structs whose fields can be written in cells.
+fn table(values: &[Value]) -> Option<(Vec<&str>, Vec<Vec<&str>>)> {
+  let mut columns: Vec<&str> = Vec::new();
+  for value in values {
+    match value {
+      Value::Fields(fields) if !fields.is_empty() => {
+        for (name, value) in fields {
+          if !matches!(value, Value::Text(_)) {
+            return None;
+          }
+          if !columns.contains(&name.as_str()) {
+            columns.push(name);
+          }
+        }
+      }
+      _ => return None,
+    }
+  }
+  if columns.is_empty() {
+    return None;
+  }
+  let rows = values
+    .iter()
+    .map(|value| {
+      columns
+        .iter()
+        .map(|column| match value {
+          Value::Fields(fields) => fields
+            .iter()
+            .find_map(|(name, value)| match value {
+              Value::Text(text) if name == column => Some(text.as_str()),
+              _ => None,
+            })
+            .unwrap_or(""),
+          _ => "",
+        })
+        .collect()
+    })
+    .collect();
+  Some((columns, rows))
+}
+
+/// Output format of a `MarkupWriter`.
+trait Format {
+  fn escape(text: &str) -> String;
+  fn heading(out: &mut String, level: usize, text: &str);
+  fn paragraph(out: &mut String, text: &str);
+  fn definitions(out: &mut String, fields: &[(&str, &str)]);
+  fn items(out: &mut String, items: &[&str]);
+  fn table(out: &mut String, columns: &[&str], rows: &[Vec<&str>]);
+}
+
+fn render<F: Format>(out: &mut String, value: &Value, level: usize) {
+  let level = level.min(6);
+  match value {
+    Value::Text(text) => F::paragraph(out, text),
+    Value::Fields(fields) => {
+      let simple: Vec<(&str, &str)> = fields
+        .iter()
+        .filter_map(|(name, value)| match value {
+          Value::Text(text) => Some((name.as_str(), text.as_str())),
+          _ => None,
+        })
+        .collect();
+      if !simple.is_empty() {
+        F::definitions(out, &simple);
+      }
+      for (name, value) in fields {
+        if !matches!(value, Value::Text(_)) {
+          F::heading(out, level, name);
+          render::<F>(out, value, level + 1);
+        }
+      }
+    }
+    Value::List(values) if values.is_empty() => F::paragraph(out, "(empty)"),
+    Value::List(values) => {
+      let texts: Option<Vec<&str>> = values
+        .iter()
+        .map(|value| match value {
+          Value::Text(text) => Some(text.as_str()),
+          _ => None,
+        })
+        .collect();
+      if let Some(texts) = texts {
+        F::items(out, &texts);
+      } else if let Some((columns, rows)) = table(values) {
+        F::table(out, &columns, &rows);
+      } else {
+        for (i, value) in values.iter().enumerate() {
+          F::heading(out, level, &format!("[{}]", i));
+          render::<F>(out, value, level + 1);
+        }
+      }
+    }
+  }
+}
+
+struct Markdown;
+
+impl Markdown {
+  /// Escapes text which begins a block, where it could otherwise be taken
+  /// for the start of a heading, list, or rule.
+  fn escape_start(text: &str) -> String {
+    let text = Markdown::escape(text);
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    match text[digits..].chars().next() {
+      Some('.') | Some(')') if digits > 0 => {
+        format!("{}\\{}", &text[..digits], &text[digits..])
+      }
+      Some('#') | Some('-') | Some('+') | Some('=') if digits == 0 => {
+        format!("\\{}", text)
+      }
+      _ => text,
+    }
+  }
+}
+
+impl Format for Markdown {
+  fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+      match c {
+        '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~' | '&' => {
+          res.push('\\');
+          res.push(c);
+        }
+        '\n' => res.push_str("<br>"),
+        '\r' => {}
+        c => res.push(c),
+      }
+    }
+    res
+  }
+
+  fn heading(out: &mut String, level: usize, text: &str) {
+    out.push_str(&format!("{} {}\n\n", "#".repeat(level), Self::escape(text)));
+  }
+
+  fn paragraph(out: &mut String, text: &str) {
+    out.push_str(&format!("{}\n\n", Markdown::escape_start(text)));
+  }
+
+  fn definitions(out: &mut String, fields: &[(&str, &str)]) {
+    for (name, value) in fields {
+      let (name, value) = (Self::escape(name), Self::escape(value));
+      out.push_str(&format!("- **{}**: {}\n", name, value));
+    }
+    out.push('\n');
+  }
+
+  fn items(out: &mut String, items: &[&str]) {
+    for item in items {
+      out.push_str(&format!("- {}\n", Markdown::escape_start(item)));
+    }
+    out.push('\n');
+  }
+
+  fn table(out: &mut String, columns: &[&str], rows: &[Vec<&str>]) {
+    let row = |cells: &mut dyn Iterator<Item = String>| {
+      let cells: Vec<String> = cells.collect();
+      format!("| {} |\n", cells.join(" | "))
+    };
+    out.push_str(&row(&mut columns.iter().map(|c| Self::escape(c))));
+    out.push_str(&row(&mut columns.iter().map(|_| "---".to_string())));
+    for cells in rows {
+      out.push_str(&row(&mut cells.iter().map(|c| Self::escape(c))));
+    }
+    out.push('\n');
+  }
+}
+
+struct Html;
+
+impl Format for Html {
+  fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+      match c {
+        '&' => res.push_str("&amp;"),
+        '<' => res.push_str("&lt;"),
+        '>' => res.push_str("&gt;"),
+        '"' => res.push_str("&quot;"),
+        '\'' => res.push_str("&#39;"),
+        '\n' => res.push_str("<br>"),
+        '\r' => {}
+        c => res.push(c),
+      }
+    }
+    res
+  }
+
+  fn heading(out: &mut String, level: usize, text: &str) {
+    let text = Self::escape(text);
+    out.push_str(&format!("<h{0}>{1}</h{0}>\n", level, text));
+  }
+
+  fn paragraph(out: &mut String, text: &str) {
+    out.push_str(&format!("<p>{}</p>\n", Self::escape(text)));
+  }
+
+  fn definitions(out: &mut String, fields: &[(&str, &str)]) {
+    out.push_str("<dl>\n");
+    for (name, value) in fields {
+      out.push_str(&format!("<dt>{}</dt>\n", Self::escape(name)));
+      out.push_str(&format!("<dd>{}</dd>\n", Self::escape(value)));
+    }
+    out.push_str("</dl>\n");
+  }
+
+  fn items(out: &mut String, items: &[&str]) {
+    out.push_str("<ul>\n");
+    for item in items {
+      out.push_str(&format!("<li>{}</li>\n", Self::escape(item)));
+    }
+    out.push_str("</ul>\n");
+  }
+
+  fn table(out: &mut String, columns: &[&str], rows: &[Vec<&str>]) {
+    out.push_str("<table>\n<tr>");
+    for column in columns {
+      out.push_str(&format!("<th>{}</th>", Self::escape(column)));
+    }
+    out.push_str("</tr>\n");
+    for cells in rows {
+      out.push_str("<tr>");
+      for cell in cells {
+        out.push_str(&format!("<td>{}</td>", Self::escape(cell)));
+      }
+      out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+  }
+}
+
+macro_rules! markup_writer_impls {
+  ($tname:ident) => {
+    impl $tname {
+      pub fn new() -> Self {
+        $tname::default()
+      }
+    }
+
+    impl PromptResponder for $tname {
+      fn begin_scope(&mut self, name: &str, size: Option<usize>) -> Result<()> {
+        self.recorder.begin_scope(name, size);
+        Ok(())
+      }
+
+      fn end_scope(&mut self) -> Result<()> {
+        self.recorder.end_scope();
+        Ok(())
+      }
+
+      fn respond(
+        &mut self,
+        kind: RequestKind,
+        prompt: &str,
+        response: &str,
+      ) -> Result<()> {
+        self.recorder.respond(kind, prompt, response);
+        Ok(())
+      }
+    }
+  };
+}
+
+/// Responder which renders the value it is sent as a Markdown document.
+///
+/// Questions and units are left out. Structs become lists of their fields,
+/// seqs of structs become tables, and other seqs become lists or headed
+/// sections. Fields and map entries with compound values become headings
+/// over their contents, as does the type of the value itself. The names and
+/// sizes of scopes are used to tell the fields of structs from other scopes,
+/// so the value should be sent by `to_bare_prompt` or `to_prompt`. Text is
+/// escaped, with line breaks written as `<br>`.
+#[derive(Default)]
+pub struct MarkdownWriter {
+  recorder: Recorder,
+}
+
+markup_writer_impls!(MarkdownWriter);
+
+impl MarkdownWriter {
+  /// Returns the Markdown document for the value sent so far.
+  pub fn into_string(self) -> String {
+    let (title, value) = self.recorder.finish();
+    let mut out = String::new();
+    let level = match title {
+      Some(title) => {
+        Markdown::heading(&mut out, 1, &title);
+        2
+      }
+      None => 1,
+    };
+    render::<Markdown>(&mut out, &value, level);
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+  }
+}
+
+/// Responder which renders the value it is sent as a standalone HTML page.
+///
+/// The layout is as for `MarkdownWriter`, with structs becoming definition
+/// lists. Text is escaped, with line breaks written as `<br>`.
+#[derive(Default)]
+pub struct HtmlWriter {
+  recorder: Recorder,
+}
+
+markup_writer_impls!(HtmlWriter);
+
+impl HtmlWriter {
+  /// Returns the HTML page for the value sent so far.
+  pub fn into_string(self) -> String {
+    let (title, value) = self.recorder.finish();
+    let mut body = String::new();
+    let level = match &title {
+      Some(title) => {
+        Html::heading(&mut body, 1, title);
+        2
+      }
+      None => 1,
+    };
+    render::<Html>(&mut body, &value, level);
+    format!(
+      "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+       <title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+      Html::escape(title.as_deref().unwrap_or("")),
+      body
+    )
+  }
+}
@@ -0,0 +1,213 @@
+use crate::error::Result;
+use crate::prompt::{PromptResponder, RequestKind};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Sequence of scope names leading to a location within a value.
+///
+/// Paths are written with their scope names separated by full stops, except
+/// that element scopes such as `[0]` are appended directly to the preceding
+/// name, e.g. `Parent.children.seq[0].Child.name`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ScopePath(Vec<String>);
+
+impl ScopePath {
+  pub fn new() -> Self {
+    ScopePath(Vec::new())
+  }
+
+  /// Returns the scope names making up the path.
+  pub fn names(&self) -> &[String] {
+    &self.0
+  }
+
+  /// Returns the number of scopes in the path.
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  /// Returns true if the path refers to the root scope.
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Appends a scope name to the path.
+  pub fn push(&mut self, name: &str) {
+    self.0.push(name.to_string());
+  }
+
+  /// Removes the innermost scope name from the path.
+  pub fn pop(&mut self) -> Option<String> {
+    self.0.pop()
+  }
+
+  /// Returns the first `len` scopes of the path.
+  pub fn prefix(&self, len: usize) -> ScopePath {
+    ScopePath(self.0[..len].to_vec())
+  }
+
+  /// Returns true if `prefix` is a prefix of this path.
+  pub fn starts_with(&self, prefix: &ScopePath) -> bool {
+    self.0.starts_with(&prefix.0)
+  }
+
+  /// Returns the length of the shortest prefix of this path which ends with
+  /// the scope names of `pattern`, if any.
+  pub fn find(&self, pattern: &ScopePath) -> Option<usize> {
+    (pattern.len()..=self.len())
+      .find(|len| self.0[..*len].ends_with(&pattern.0))
+  }
+}
+
+impl Display for ScopePath {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    for (i, name) in self.0.iter().enumerate() {
+      if i > 0 && !name.starts_with('[') {
+        fmt.write_str(".")?;
+      }
+      fmt.write_str(name)?;
+    }
+    Ok(())
+  }
+}
+
+impl FromStr for ScopePath {
+  type Err = std::convert::Infallible;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    let mut path = ScopePath::new();
+    for part in s.split('.').filter(|p| !p.is_empty()) {
+      let mut rest = part;
+      while let Some((i, _)) =
+        rest.char_indices().skip(1).find(|(_, c)| *c == '[')
+      {
+        path.push(&rest[..i]);
+        rest = &rest[i..];
+      }
+      path.push(rest);
+    }
+    Ok(path)
+  }
+}
+
+/// A response recorded together with the path at which it was given.
+pub(crate) struct PathEntry {
+  pub path: ScopePath,
+  pub kind: RequestKind,
+  pub prompt: String,
+  pub response: String,
+}
+
+/// Responder which records the path of every non-synthetic response.
+pub(crate) struct PathRecorder {
+  path: ScopePath,
+  entries: Vec<PathEntry>,
+}
+
+impl PathRecorder {
+  pub fn new() -> Self {
+    PathRecorder {
+      path: ScopePath::new(),
+      entries: Vec::new(),
+    }
+  }
+
+  pub fn entries(&self) -> &[PathEntry] {
+    &self.entries
+  }
+}
+
+impl PromptResponder for PathRecorder {
+  fn begin_scope(&mut self, name: &str, _size: Option<usize>) -> Result<()> {
+    self.path.push(name);
+    Ok(())
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    self.path.pop();
+    Ok(())
+  }
+
+  fn respond(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    response: &str,
+  ) -> Result<()> {
+    if kind != RequestKind::Synthetic {
+      self.entries.push(PathEntry {
+        path: self.path.clone(),
+        kind,
+        prompt: prompt.to_string(),
+        response: response.to_string(),
+      });
+    }
+    Ok(())
+  }
+}
+
+/// Renders entries within the scope `root` as a compact tree, highlighting
+/// the scopes along the path `current`.
+pub(crate) fn render_tree(
+  entries: &[PathEntry],
+  root: &ScopePath,
+  current: &ScopePath,
+) -> Vec<String> {
+  let mut lines = Vec::new();
+  if root.is_empty() {
+    render_group(entries, 0, 0, current, &mut lines);
+  } else {
+    let highlight = current.starts_with(root);
+    lines.push(tree_line(&root.to_string(), 0, highlight));
+    render_group(entries, root.len(), 1, current, &mut lines);
+  }
+  lines
+}
+
+fn render_group(
+  entries: &[PathEntry],
+  depth: usize,
+  indent: usize,
+  current: &ScopePath,
+  lines: &mut Vec<String>,
+) {
+  let mut i = 0;
+  while i < entries.len() {
+    let first = &entries[i];
+    if first.path.len() == depth {
+      let text = format!("{}: {}", first.prompt, first.response);
+      lines.push(tree_line(&text, indent, false));
+      i += 1;
+      continue;
+    }
+    let names = first.path.names();
+    let len = entries[i..]
+      .iter()
+      .take_while(|e| e.path.len() > depth && e.path.0[depth] == names[depth])
+      .count();
+    let group = &entries[i..i + len];
+    // Chains of scopes shared by the whole group are merged into one line
+    let mut end = depth + 1;
+    while group
+      .iter()
+      .all(|e| e.path.len() > end && e.path.0[end] == names[end])
+    {
+      end += 1;
+    }
+    let header = ScopePath(names[depth..end].to_vec()).to_string();
+    let highlight = current.starts_with(&first.path.prefix(end));
+    if len == 1 && first.path.len() == end {
+      let text = format!("{}: {}", header, first.response);
+      lines.push(tree_line(&text, indent, highlight));
+    } else {
+      lines.push(tree_line(&header, indent, highlight));
+      render_group(group, end, indent + 1, current, lines);
+    }
+    i += len;
+  }
+}
+
+fn tree_line(text: &str, indent: usize, highlight: bool) -> String {
+  let marker = if highlight { ">" } else { " " };
+  format!("{} {:indent$}{}", marker, "", text, indent = 2 * indent)
+}
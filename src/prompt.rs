@@ -1,4 +1,5 @@
 use crate::error::{Error, Result, UserAction};
+use crate::path::{self, PathEntry, ScopePath};
 use std::str::FromStr;
 
 /// Represents the kind of a prompt request or response.
@@ -92,11 +93,14 @@ impl<P: PromptRequester> PromptRequester for &mut P {
 /// Prompt decorator which allows `UserAction`s to be triggered in-band.
 ///
 /// This prompt intercepts responses which begin with an exclamation mark.
-/// Entering the meta-commands `!cancel`, `!undo`, and `!restart` as a response
-/// will cause the request to fail with the corresponding `UserAction`. The
-/// commands may be abbreviated to single letters. The undo and restart
-/// commands may be followed by a number otherwise `Undo(1)` and `Restart(0)`
-/// is implied. Responses that actually begin with an exclamation mark can be
+/// Entering the meta-commands `!cancel`, `!undo`, `!redo`, `!restart`, `!up`,
+/// `!back`, `!show`, and `!history` as a response will cause the request to
+/// fail with the corresponding `UserAction`. The commands other than redo, up,
+/// and history may be abbreviated to single letters. The undo, redo, and
+/// restart commands may be followed by a number otherwise `Undo(1)`,
+/// `Redo(1)`, and `Restart(0)` is implied. The back command must be followed
+/// by a space and a scope path, which the show command also accepts
+/// optionally. Responses that actually begin with an exclamation mark can be
 /// escaped by doubling the exclamation mark.
 pub struct MetaCommandPrompt<P> {
   inner: P,
@@ -136,7 +140,12 @@ const HELP_TEXT: &[&str] = &[
   "  !!              - Escape responses beginning with an exclamation mark",
   "  !c[ancel]       - Cancel deserialisation",
   "  !u[ndo][<n>]    - Undo the previous or the last <n> responses",
+  "  !redo[<n>]      - Redo the last or the last <n> undone responses",
   "  !r[estart][<n>] - Restart from the beginning or from the <n>th response",
+  "  !up             - Undo to the start of the current scope",
+  "  !b[ack] <path>  - Go back to the start of the scope named by <path>",
+  "  !s[how] [<p>]   - Show responses so far or within scope <p> (. = current)",
+  "  !history        - List responses so far with their numbers",
   "  !h[elp]         - This message",
 ];
 
@@ -160,6 +169,16 @@ impl<P: PromptRequester> PromptRequester for MetaCommandPrompt<P> {
         s.replace_range(0..1, "");
         return Ok(s);
       }
+      if let Some((cmd, path)) = s.split_once(' ') {
+        let path = path.trim();
+        if (cmd == "!b" || cmd == "!back") && !path.is_empty() {
+          return Err(Error::UserAction(UserAction::Back(path.to_string())));
+        }
+        if (cmd == "!s" || cmd == "!show") && !path.is_empty() {
+          let path = Some(path.to_string());
+          return Err(Error::UserAction(UserAction::Show(path)));
+        }
+      }
       let prefix = s.trim_end_matches(|c: char| c.is_ascii_digit());
       let suffix_str = &s[prefix.len()..];
       let suffix = if suffix_str.is_empty() {
@@ -183,6 +202,17 @@ impl<P: PromptRequester> PromptRequester for MetaCommandPrompt<P> {
         ("!r", Some(Some(n))) | ("!restart", Some(Some(n))) => {
           return Err(Error::UserAction(UserAction::Restart(n)))
         }
+        ("!redo", None) => return Err(Error::UserAction(UserAction::Redo(1))),
+        ("!redo", Some(Some(n))) => {
+          return Err(Error::UserAction(UserAction::Redo(n)))
+        }
+        ("!up", None) => return Err(Error::UserAction(UserAction::Up)),
+        ("!history", None) => {
+          return Err(Error::UserAction(UserAction::History))
+        }
+        ("!s", None) | ("!show", None) => {
+          return Err(Error::UserAction(UserAction::Show(None)))
+        }
         ("!h", None) | ("!help", None) => {
           self.report(
             ReportKind::Help,
@@ -210,11 +240,16 @@ impl<P: PromptRequester> PromptRequester for MetaCommandPrompt<P> {
   }
 }
 
-#[derive(Clone, Debug)]
 enum ReplayState {
   Disabled,
   Recording,
-  Replaying(std::vec::IntoIter<String>),
+  Replaying(std::vec::IntoIter<PathEntry>),
+}
+
+struct Revision {
+  start: usize,
+  scope: ScopePath,
+  tail: Vec<PathEntry>,
 }
 
 /// Prompt decorator which logs responses and can replay them.
@@ -224,10 +259,30 @@ enum ReplayState {
 /// backwards once a response has been submitted. It's necessary to start the
 /// deserialiser again, but the `ReplayPrompt` can quickly bring it up to the
 /// correct point by replaying its log.
+///
+/// Each response is logged with the path of the scope in which it was given.
+/// This allows the `Up` and `Back` user actions, which refer to scopes, to be
+/// translated into `Restart` actions at the corresponding log positions. The
+/// `Show` and `History` user actions are handled by displaying the log, after
+/// which the request is repeated. The numbers listed by the latter are the
+/// positions to which `Restart` returns.
+///
+/// The kind, prompt, and path of each request are also logged and checked
+/// when its response is replayed. If they don't match, the rest of the log is
+/// discarded and the prompt continues interactively from that point, or fails
+/// with `Error::ReplayMismatch` if the inner prompt isn't interactive.
 pub struct ReplayPrompt<P> {
   inner: P,
-  log: Vec<String>,
+  log: Vec<PathEntry>,
   state: ReplayState,
+  path: ScopePath,
+  sizes: Vec<Option<usize>>,
+  shown: usize,
+  quiet: bool,
+  replayed: usize,
+  revision: Option<Revision>,
+  redo: Vec<PathEntry>,
+  unverified: usize,
 }
 
 impl<P> ReplayPrompt<P> {
@@ -236,19 +291,43 @@ impl<P> ReplayPrompt<P> {
       inner,
       log: Vec::new(),
       state: ReplayState::Disabled,
+      path: ScopePath::new(),
+      sizes: Vec::new(),
+      shown: 0,
+      quiet: false,
+      replayed: 0,
+      revision: None,
+      redo: Vec::new(),
+      unverified: 0,
     }
   }
 
+  /// Sets whether replaying is quiet.
+  ///
+  /// When quiet, replayed responses and the scopes around them are not passed
+  /// on to the inner prompt. Instead, the number of responses replayed is
+  /// reported and the scopes enclosing the next interactive request are begun
+  /// just before it is made.
+  pub fn set_quiet(&mut self, quiet: bool) {
+    self.quiet = quiet;
+  }
+
   /// Clear log and disable recording.
   pub fn reset(&mut self) {
     self.log.clear();
     self.state = ReplayState::Disabled;
+    self.revision = None;
+    self.redo.clear();
+    self.unverified = 0;
   }
 
   /// Start recording responses.
   pub fn record(&mut self) {
     self.log.clear();
     self.state = ReplayState::Recording;
+    self.revision = None;
+    self.redo.clear();
+    self.unverified = 0;
   }
 
   /// Replay log and continue recording new responses.
@@ -262,25 +341,292 @@ impl<P> ReplayPrompt<P> {
     }
   }
 
+  /// Returns the number of responses in the log.
+  pub fn position(&self) -> usize {
+    self.log.len()
+  }
+
+  /// Returns the responses in the log.
+  pub fn responses(&self) -> Vec<String> {
+    self.log.iter().map(|e| e.response.clone()).collect()
+  }
+
+  /// Returns the prompt and response of each request in the log.
+  pub(crate) fn labelled_responses(&self) -> Vec<(String, String)> {
+    self
+      .log
+      .iter()
+      .map(|e| (e.prompt.clone(), e.response.clone()))
+      .collect()
+  }
+
+  /// Returns the responses in the log followed by those still waiting to be
+  /// replayed.
+  pub(crate) fn all_responses(&self) -> Vec<String> {
+    let mut responses = self.responses();
+    if let ReplayState::Replaying(pending) = &self.state {
+      responses.extend(pending.as_slice().iter().map(|e| e.response.clone()));
+    }
+    responses
+  }
+
+  /// Replace the log with the given responses, for example to resume a
+  /// previous session. Call `replay` to bring the deserialiser up to date.
+  ///
+  /// Unlike logged responses, these responses can't be checked against the
+  /// requests they are replayed to.
+  pub fn load<I: IntoIterator<Item = String>>(&mut self, responses: I) {
+    self.log = responses
+      .into_iter()
+      .map(|response| PathEntry {
+        path: ScopePath::new(),
+        kind: RequestKind::Datum,
+        prompt: String::new(),
+        response,
+      })
+      .collect();
+    self.revision = None;
+    self.redo.clear();
+    self.unverified = self.log.len();
+  }
+
   /// Remove the last n responses from the log.
+  ///
+  /// The removed responses can be restored by `redo` until a response which
+  /// differs from them is given.
   pub fn undo(&mut self, n: usize) {
     let len = self.log.len();
-    self.log.truncate(len - std::cmp::min(len, n));
+    self.restart_from(len - std::cmp::min(len, n));
   }
 
   /// Truncate the log to the first nth responses in the log.
+  ///
+  /// The removed responses can be restored by `redo` until a response which
+  /// differs from them is given.
   pub fn restart_from(&mut self, n: usize) {
+    if n < self.log.len() {
+      let removed = self.log.split_off(n);
+      self.redo.extend(removed.into_iter().rev());
+    }
+    self.truncate(n);
+  }
+
+  /// Restore up to n of the most recently removed responses to the log.
+  /// Returns the number of responses restored.
+  pub fn redo(&mut self, n: usize) -> usize {
+    let n = std::cmp::min(n, self.redo.len());
+    for _ in 0..n {
+      let entry = self.redo.pop().unwrap();
+      self.log.push(entry);
+    }
+    n
+  }
+
+  /// Truncate the log without allowing the removed responses to be restored.
+  pub(crate) fn discard_from(&mut self, n: usize) {
+    self.truncate(n);
+  }
+
+  /// Remove the responses given within the scope matching `path` from the
+  /// log, keeping the later responses aside.
+  ///
+  /// When the log is next replayed, the responses within the scope will be
+  /// requested again and then the later responses will be replayed. Leading
+  /// questions in the scope itself, such as whether a sequence element is
+  /// present, are kept so that the responses set aside still fit. Returns
+  /// false if no responses were given within the scope.
+  pub fn revise(&mut self, path: &str) -> bool {
+    let (mut start, scope) = match self.find_scope(path) {
+      Some(found) => found,
+      None => return false,
+    };
+    let end = start
+      + self.log[start..]
+        .iter()
+        .take_while(|e| e.path.starts_with(&scope))
+        .count();
+    while start < end
+      && self.log[start].kind == RequestKind::Question
+      && self.log[start].path == scope
+    {
+      start += 1;
+    }
+    if start == end {
+      return false;
+    }
+    let tail = self.log.split_off(end);
+    self.log.truncate(start);
+    self.revision = Some(Revision { start, scope, tail });
+    self.redo.clear();
+    true
+  }
+
+  /// Returns the position in the log of the first response given within the
+  /// scope matching `path`, if any.
+  ///
+  /// A path matches a scope if its scope names appear in order within the
+  /// path of the scope, with paths which match exactly preferred.
+  pub fn position_of(&self, path: &str) -> Option<usize> {
+    self.find_scope(path).map(|(i, _)| i)
+  }
+
+  /// Returns the position in the log of the first of the latest responses
+  /// given within the scope at `scope`, if the log ends with any.
+  pub(crate) fn scope_start(&self, scope: &ScopePath) -> Option<usize> {
+    let run = self
+      .log
+      .iter()
+      .rev()
+      .take_while(|e| e.path.starts_with(scope))
+      .count();
+    if run > 0 {
+      Some(self.log.len() - run)
+    } else {
+      None
+    }
+  }
+
+  /// Returns the position in the log of the first response given within the
+  /// innermost currently open scope which contains any responses.
+  pub fn current_scope_start(&self) -> Option<usize> {
+    self.current_scope().map(|(i, _)| i)
+  }
+
+  fn current_scope(&self) -> Option<(usize, ScopePath)> {
+    (0..=self.path.len()).rev().find_map(|len| {
+      let scope = self.path.prefix(len);
+      let run = self
+        .log
+        .iter()
+        .rev()
+        .take_while(|e| e.path.starts_with(&scope))
+        .count();
+      if run > 0 {
+        Some((self.log.len() - run, scope))
+      } else {
+        None
+      }
+    })
+  }
+
+  fn history(&mut self) -> Result<()>
+  where
+    P: PromptRequester,
+  {
+    for (i, entry) in self.log.iter().enumerate() {
+      let line = format!(
+        "{:>4} {} ({}): {}",
+        i, entry.path, entry.prompt, entry.response
+      );
+      self.inner.report(ReportKind::Help, &line)?;
+    }
+    Ok(())
+  }
+
+  fn show(&mut self, path: Option<&str>) -> Result<bool>
+  where
+    P: PromptRequester,
+  {
+    let (start, scope) = match path {
+      None => (0, ScopePath::new()),
+      Some(".") => match self.current_scope() {
+        Some(found) => found,
+        None => return Ok(false),
+      },
+      Some(path) => match self.find_scope(path) {
+        Some(found) => found,
+        None => return Ok(false),
+      },
+    };
+    let len = self.log[start..]
+      .iter()
+      .take_while(|e| e.path.starts_with(&scope))
+      .count();
+    if len == 0 {
+      return Ok(false);
+    }
+    let entries = &self.log[start..start + len];
+    for line in path::render_tree(entries, &scope, &self.path) {
+      self.inner.report(ReportKind::Help, &line)?;
+    }
+    Ok(true)
+  }
+
+  fn find_scope(&self, path: &str) -> Option<(usize, ScopePath)> {
+    let pattern = ScopePath::from_str(path).unwrap();
+    self
+      .log
+      .iter()
+      .position(|e| e.path.starts_with(&pattern))
+      .map(|i| (i, pattern.clone()))
+      .or_else(|| {
+        self.log.iter().enumerate().find_map(|(i, e)| {
+          e.path.find(&pattern).map(|len| (i, e.path.prefix(len)))
+        })
+      })
+  }
+
+  fn truncate(&mut self, n: usize) {
     self.log.truncate(n);
+    self.unverified = std::cmp::min(self.unverified, n);
+    if let Some(rev) = &self.revision {
+      if n < rev.start {
+        self.revision = None;
+      }
+    }
+  }
+
+  fn is_silent(&self) -> bool {
+    self.quiet && matches!(self.state, ReplayState::Replaying(_))
+  }
+
+  fn resume(&mut self) -> Result<()>
+  where
+    P: PromptRequester,
+  {
+    self.state = ReplayState::Recording;
+    if self.quiet && self.replayed > 0 {
+      let msg = format!("Replayed responses: {}", self.replayed);
+      self.inner.report(ReportKind::Help, &msg)?;
+    }
+    self.replayed = 0;
+    while self.shown < self.path.len() {
+      let name = &self.path.names()[self.shown];
+      self.inner.begin_scope(name, self.sizes[self.shown])?;
+      self.shown += 1;
+    }
+    Ok(())
+  }
+
+  fn push_log(&mut self, kind: RequestKind, prompt: &str, response: &str) {
+    self.log.push(PathEntry {
+      path: self.path.clone(),
+      kind,
+      prompt: prompt.to_string(),
+      response: response.to_string(),
+    });
   }
 }
 
 impl<P: PromptResponder> PromptResponder for ReplayPrompt<P> {
   fn begin_scope(&mut self, name: &str, size: Option<usize>) -> Result<()> {
+    self.path.push(name);
+    self.sizes.push(size);
+    if self.is_silent() {
+      return Ok(());
+    }
+    self.shown += 1;
     self.inner.begin_scope(name, size)
   }
 
   fn end_scope(&mut self) -> Result<()> {
-    self.inner.end_scope()
+    self.path.pop();
+    self.sizes.pop();
+    if self.shown > self.path.len() {
+      self.shown -= 1;
+      self.inner.end_scope()?;
+    }
+    Ok(())
   }
 
   fn respond(
@@ -291,9 +637,12 @@ impl<P: PromptResponder> PromptResponder for ReplayPrompt<P> {
   ) -> Result<()> {
     if kind != RequestKind::Synthetic {
       if let ReplayState::Recording = self.state {
-        self.log.push(response.to_string());
+        self.push_log(kind, prompt, response);
       };
     };
+    if self.is_silent() {
+      return Ok(());
+    }
     self.inner.respond(kind, prompt, response)
   }
 }
@@ -313,18 +662,85 @@ impl<P: PromptRequester> PromptRequester for ReplayPrompt<P> {
     prompt: &str,
     variants: &'static [&'static str],
   ) -> Result<String> {
+    if let (ReplayState::Recording, Some(rev)) = (&self.state, &self.revision) {
+      if !self.path.starts_with(&rev.scope) {
+        let tail = self.revision.take().unwrap().tail;
+        self.state = ReplayState::Replaying(tail.into_iter());
+      }
+    }
+
     if let ReplayState::Replaying(iter) = &mut self.state {
-      if let Some(res) = iter.next() {
-        self.log.push(res.clone());
-        self.inner.respond(kind, prompt, &res)?;
-        return Ok(res);
-      };
-      self.state = ReplayState::Recording;
+      match iter.next() {
+        Some(entry) => {
+          let fits = entry.kind == kind
+            && entry.prompt == prompt
+            && entry.path == self.path;
+          if self.unverified > 0 || fits {
+            self.unverified = self.unverified.saturating_sub(1);
+            self.replayed += 1;
+            let res = entry.response;
+            self.push_log(kind, prompt, &res);
+            if !self.quiet {
+              self.inner.respond(kind, prompt, &res)?;
+            }
+            return Ok(res);
+          }
+          // The rest of the log can't be trusted to fit either
+          self.revision = None;
+          self.resume()?;
+          self.inner.report(
+            ReportKind::BadResponse,
+            &format!("Cannot replay response '{}' here", entry.response),
+          )?;
+          if !self.inner.is_interactive() {
+            return Err(Error::ReplayMismatch);
+          }
+        }
+        None => self.resume()?,
+      }
     }
 
-    let res = self.inner.request(kind, prompt, variants)?;
+    let res = loop {
+      // Scope-relative actions are translated into restarts while the
+      // current path is still known
+      let (pos, msg) = match self.inner.request(kind, prompt, variants) {
+        Err(Error::UserAction(UserAction::Up)) => {
+          (self.current_scope_start(), "Nothing to undo")
+        }
+        Err(Error::UserAction(UserAction::Back(path))) => {
+          (self.position_of(&path), "No responses in scope")
+        }
+        Err(Error::UserAction(UserAction::History)) => {
+          self.history()?;
+          continue;
+        }
+        Err(Error::UserAction(UserAction::Show(path))) => {
+          if !self.show(path.as_deref())? {
+            self
+              .inner
+              .report(ReportKind::Help, "No responses in scope")?;
+          }
+          continue;
+        }
+        res => break res?,
+      };
+      match pos {
+        Some(n) => return Err(Error::UserAction(UserAction::Restart(n))),
+        None => self.inner.report(ReportKind::BadResponse, msg)?,
+      }
+      if !self.inner.is_interactive() {
+        return Err(Error::BadResponse);
+      }
+    };
     if let ReplayState::Recording = self.state {
-      self.log.push(res.clone());
+      self.push_log(kind, prompt, &res);
+      // Removed responses can only be restored while the new responses match
+      match self.redo.last() {
+        Some(e) if e.path == self.path && e.response == res => {
+          self.redo.pop();
+        }
+        _ => self.redo.clear(),
+      }
     }
     Ok(res)
   }
@@ -433,3 +849,229 @@ impl<P: PromptRequester> PromptRequester for CompactPrompt<P> {
     self.inner.report(kind, msg)
   }
 }
+
+/// Prompt which answers requests with a fixed sequence of responses.
+///
+/// The prompt is not interactive, and requests made after the responses have
+/// run out fail with `Error::OutOfResponses`. Output and reports are ignored.
+pub struct ResponsesPrompt<I> {
+  responses: I,
+}
+
+impl<I: Iterator<Item = String>> ResponsesPrompt<I> {
+  pub fn new<R: IntoIterator<IntoIter = I>>(responses: R) -> Self {
+    ResponsesPrompt {
+      responses: responses.into_iter(),
+    }
+  }
+}
+
+impl<I> PromptResponder for ResponsesPrompt<I> {
+  fn begin_scope(&mut self, _name: &str, _size: Option<usize>) -> Result<()> {
+    Ok(())
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    Ok(())
+  }
+
+  fn respond(
+    &mut self,
+    _kind: RequestKind,
+    _prompt: &str,
+    _response: &str,
+  ) -> Result<()> {
+    Ok(())
+  }
+}
+
+impl<I: Iterator<Item = String>> PromptRequester for ResponsesPrompt<I> {
+  fn is_interactive(&self) -> bool {
+    false
+  }
+
+  fn request(
+    &mut self,
+    _kind: RequestKind,
+    _prompt: &str,
+    _variants: &'static [&'static str],
+  ) -> Result<String> {
+    self.responses.next().ok_or(Error::OutOfResponses)
+  }
+
+  fn report(&mut self, _kind: ReportKind, _msg: &str) -> Result<()> {
+    Ok(())
+  }
+}
+
+/// Returns the index of an element scope such as `[2]`.
+fn element_index(name: &str) -> Option<usize> {
+  name.strip_prefix('[')?.strip_suffix(']')?.parse().ok()
+}
+
+/// Prompt decorator which pages long output and collapses long seqs and maps.
+///
+/// When the inner prompt is interactive, output pauses every page with a
+/// `--More--` request. Responding with `q` hides the rest of the output, `a`
+/// turns paging off, and anything else shows the next page. Elements and
+/// entries beyond the limit set by `set_max_elements` are hidden whether or
+/// not the prompt is interactive, with the number hidden reported in their
+/// place.
+pub struct PagerPrompt<P> {
+  inner: P,
+  page_size: Option<usize>,
+  max_elements: Option<usize>,
+  lines: usize,
+  quit: bool,
+  // Numbers of elements hidden within each visible scope
+  scopes: Vec<usize>,
+  // Depth of hidden scopes, whether the outermost of them has been sent a
+  // response, and whether it ends its seq
+  hidden: usize,
+  hidden_answered: bool,
+  hidden_end: bool,
+}
+
+impl<P: PromptRequester> PagerPrompt<P> {
+  /// Creates a pager with pages the height of the terminal given by the
+  /// `LINES` environment variable, or 24 lines if it isn't set.
+  pub fn new(inner: P) -> Self {
+    let lines = std::env::var("LINES")
+      .ok()
+      .and_then(|s| s.parse::<usize>().ok())
+      .unwrap_or(24);
+    PagerPrompt {
+      inner,
+      page_size: Some(lines.saturating_sub(1).max(1)),
+      max_elements: None,
+      lines: 0,
+      quit: false,
+      scopes: Vec::new(),
+      hidden: 0,
+      hidden_answered: false,
+      hidden_end: false,
+    }
+  }
+
+  /// Sets the number of lines shown before pausing, or turns paging off.
+  pub fn set_page_size(&mut self, page_size: Option<usize>) {
+    self.page_size = page_size;
+  }
+
+  /// Sets the number of elements or entries shown for each seq or map.
+  pub fn set_max_elements(&mut self, max_elements: Option<usize>) {
+    self.max_elements = max_elements;
+  }
+
+  /// Accounts for a line of output, returning false if it should be hidden.
+  fn line(&mut self) -> Result<bool> {
+    if self.quit {
+      return Ok(false);
+    }
+    if let Some(page_size) = self.page_size {
+      if self.lines >= page_size && self.inner.is_interactive() {
+        let s = self.inner.request(RequestKind::Question, "--More--", &[])?;
+        match s.trim() {
+          "q" | "Q" => {
+            self.quit = true;
+            return Ok(false);
+          }
+          "a" | "A" => self.page_size = None,
+          _ => {}
+        }
+        self.lines = 0;
+      }
+    }
+    self.lines += 1;
+    Ok(true)
+  }
+}
+
+impl<P: PromptRequester> PromptResponder for PagerPrompt<P> {
+  fn begin_scope(&mut self, name: &str, size: Option<usize>) -> Result<()> {
+    if self.hidden > 0 {
+      self.hidden += 1;
+      return Ok(());
+    }
+    let over_limit = match (element_index(name), self.max_elements) {
+      (Some(i), Some(max)) => i >= max,
+      _ => false,
+    };
+    if over_limit || !self.line()? {
+      if let Some(n) = self.scopes.last_mut() {
+        *n += 1;
+      }
+      self.hidden = 1;
+      self.hidden_answered = false;
+      self.hidden_end = false;
+      return Ok(());
+    }
+    self.scopes.push(0);
+    self.inner.begin_scope(name, size)
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    if self.hidden > 0 {
+      self.hidden -= 1;
+      if self.hidden == 0 && self.hidden_end {
+        if let Some(n) = self.scopes.last_mut() {
+          *n -= 1;
+        }
+      }
+      return Ok(());
+    }
+    let n = self.scopes.pop().unwrap_or(0);
+    if n > 0 && self.line()? {
+      self
+        .inner
+        .report(ReportKind::Help, &format!("... {} more", n))?;
+    }
+    // Scopes which have been begun are always ended
+    self.line()?;
+    self.inner.end_scope()
+  }
+
+  fn respond(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    response: &str,
+  ) -> Result<()> {
+    if self.hidden > 0 {
+      // Element scopes begin with the question of whether to add an element,
+      // which is answered no in the scope which ends the seq or map
+      if self.hidden == 1 && !self.hidden_answered {
+        self.hidden_answered = true;
+        self.hidden_end = kind == RequestKind::Question && response == "no";
+      }
+      return Ok(());
+    }
+    if self.line()? {
+      self.inner.respond(kind, prompt, response)?;
+    }
+    Ok(())
+  }
+}
+
+impl<P: PromptRequester> PromptRequester for PagerPrompt<P> {
+  fn is_interactive(&self) -> bool {
+    self.inner.is_interactive()
+  }
+
+  fn request(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    variants: &'static [&'static str],
+  ) -> Result<String> {
+    self.lines = 0;
+    self.inner.request(kind, prompt, variants)
+  }
+
+  fn report(&mut self, kind: ReportKind, msg: &str) -> Result<()> {
+    if self.line()? {
+      self.inner.report(kind, msg)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,470 @@
+use crate::error::{Error, Result};
+use crate::prompt::{
+  PromptRequester, PromptResponder, ReportKind, RequestKind,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ALPHANUMERIC: &[u8] =
+  b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Distribution from which the lengths of seqs, maps, byte buffers and
+/// strings are drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LengthDistribution {
+  /// Lengths between the two bounds, inclusive, with equal probability.
+  Uniform(usize, usize),
+  /// Each further element is added with the given probability, so that
+  /// shorter lengths are more likely. Probabilities of one or more always
+  /// give the maximum length.
+  Geometric(f64),
+}
+
+/// Chooses the variants of enums once the depth limit has been reached, so
+/// that values end.
+///
+/// The first variant is taken unless the enum is nested within an enum of
+/// the same name which was chosen from here, in which case the variant after
+/// the one taken there is, so that each variant is tried in turn until one
+/// ends. Fails with `Error::OutOfResponses` if every variant has been tried,
+/// including when an enum of a single variant is nested within itself.
+struct DeepVariants {
+  scopes: Vec<String>,
+  // The number of scopes open and the variant taken at each choice
+  choices: Vec<(usize, usize)>,
+}
+
+impl DeepVariants {
+  fn new() -> Self {
+    DeepVariants {
+      scopes: Vec::new(),
+      choices: Vec::new(),
+    }
+  }
+
+  fn begin_scope(&mut self, name: &str) {
+    self.scopes.push(base(name).to_string());
+  }
+
+  fn end_scope(&mut self) {
+    self.scopes.pop();
+    let depth = self.scopes.len();
+    self.choices.retain(|(d, _)| *d <= depth);
+  }
+
+  /// Returns the index of the variant to take out of `count`.
+  fn choose(&mut self, count: usize) -> Result<usize> {
+    let depth = self.scopes.len();
+    let name = self.scopes.last();
+    let scopes = &self.scopes;
+    let index = self
+      .choices
+      .iter()
+      .rev()
+      .find(|(d, _)| *d > 0 && scopes.get(d - 1) == name)
+      .map_or(0, |(_, i)| i + 1);
+    if index >= count {
+      return Err(Error::OutOfResponses);
+    }
+    self.choices.push((depth, index));
+    Ok(index)
+  }
+}
+
+/// Prompt which answers each request with a random valid response.
+///
+/// Used with `from_bare_prompt`, this generates random values of any type
+/// which implements `Deserialize`. Variants, including those of bools, are
+/// chosen uniformly, and numbers are drawn from the whole range of their
+/// type, except for floats which lie between -1e6 and 1e6. Strings are made of
+/// ASCII letters and digits.
+///
+/// Once the number of open scopes reaches the depth limit, seqs and maps are
+/// ended, options are `None` and enums take their first variant. An enum
+/// nested within itself beyond the limit takes each of its other variants in
+/// turn, so that recursive types end a few scopes past the limit, and fails
+/// with `Error::OutOfResponses` if none of them ends.
+///
+/// A proptest `Strategy` isn't provided, but one can be made by mapping seeds
+/// from `any::<u64>()` to values through `RandomPrompt::seeded` and
+/// `from_bare_prompt`.
+pub struct RandomPrompt {
+  state: u64,
+  lengths: LengthDistribution,
+  max_length: usize,
+  max_depth: usize,
+  // The number of elements left to add to each open scope, if a collection
+  remaining: Vec<Option<usize>>,
+  deep: DeepVariants,
+}
+
+impl RandomPrompt {
+  /// Creates a prompt seeded from the system clock.
+  pub fn new() -> Self {
+    let nanos = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_nanos() as u64)
+      .unwrap_or(0);
+    Self::seeded(nanos)
+  }
+
+  /// Creates a prompt which always generates the same values from the same
+  /// seed.
+  pub fn seeded(seed: u64) -> Self {
+    RandomPrompt {
+      state: seed,
+      lengths: LengthDistribution::Uniform(0, 4),
+      max_length: 1024,
+      max_depth: 32,
+      remaining: Vec::new(),
+      deep: DeepVariants::new(),
+    }
+  }
+
+  /// Sets the distribution of lengths. Defaults to `Uniform(0, 4)`.
+  pub fn set_lengths(&mut self, lengths: LengthDistribution) {
+    self.lengths = lengths;
+  }
+
+  /// Sets the length beyond which lengths drawn from the distribution are cut
+  /// short. Defaults to 1024.
+  pub fn set_max_length(&mut self, max_length: usize) {
+    self.max_length = max_length;
+  }
+
+  /// Sets the number of open scopes, including those of fields, beyond which
+  /// values stop growing. Defaults to 32.
+  pub fn set_max_depth(&mut self, max_depth: usize) {
+    self.max_depth = max_depth;
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    // SplitMix64
+    self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+
+  fn next_u128(&mut self) -> u128 {
+    (self.next_u64() as u128) << 64 | self.next_u64() as u128
+  }
+
+  fn next_f64(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+  }
+
+  fn below(&mut self, n: usize) -> usize {
+    (self.next_u64() % n as u64) as usize
+  }
+
+  fn length(&mut self) -> usize {
+    let len = match self.lengths {
+      LengthDistribution::Uniform(min, max) => {
+        min + self.below(max.saturating_sub(min).saturating_add(1))
+      }
+      LengthDistribution::Geometric(p) => {
+        let mut n = 0;
+        while n < self.max_length && self.next_f64() < p {
+          n += 1;
+        }
+        n
+      }
+    };
+    std::cmp::min(len, self.max_length)
+  }
+
+  fn is_deep(&self) -> bool {
+    self.remaining.len() >= self.max_depth
+  }
+
+  fn string(&mut self) -> String {
+    let len = self.length();
+    (0..len)
+      .map(|_| ALPHANUMERIC[self.below(ALPHANUMERIC.len())] as char)
+      .collect()
+  }
+
+  fn datum(&mut self, label: &str) -> String {
+    let bits = self.next_u128();
+    match label {
+      "u8" => (bits as u8).to_string(),
+      "u16" => (bits as u16).to_string(),
+      "u32" => (bits as u32).to_string(),
+      "u64" => (bits as u64).to_string(),
+      "u128" => bits.to_string(),
+      "i8" => (bits as i8).to_string(),
+      "i16" => (bits as i16).to_string(),
+      "i32" => (bits as i32).to_string(),
+      "i64" => (bits as i64).to_string(),
+      "i128" => (bits as i128).to_string(),
+      "f32" => ((self.next_f64() * 2.0 - 1.0) as f32 * 1e6).to_string(),
+      "f64" => ((self.next_f64() * 2.0 - 1.0) * 1e6).to_string(),
+      "char" => {
+        (ALPHANUMERIC[self.below(ALPHANUMERIC.len())] as char).to_string()
+      }
+      _ => self.string(),
+    }
+  }
+
+  /// Answers whether to add another element to the collection `up` scopes
+  /// above the current one.
+  fn add_element(&mut self, up: usize) -> bool {
+    let deep = self.is_deep();
+    let n = self.remaining.len();
+    match self.remaining.get_mut(n.wrapping_sub(up + 1)) {
+      Some(Some(left)) if *left > 0 && !deep => {
+        *left -= 1;
+        true
+      }
+      _ => false,
+    }
+  }
+}
+
+impl Default for RandomPrompt {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Returns the part of a possibly compacted name after its last separator.
+fn base(name: &str) -> &str {
+  name.rsplit(" -> ").next().unwrap_or(name)
+}
+
+fn yes_no(b: bool) -> String {
+  if b { "yes" } else { "no" }.to_string()
+}
+
+impl PromptResponder for RandomPrompt {
+  fn begin_scope(&mut self, name: &str, _size: Option<usize>) -> Result<()> {
+    let remaining = match base(name) {
+      "seq" | "map" | "bytes" => Some(self.length()),
+      _ => None,
+    };
+    self.remaining.push(remaining);
+    self.deep.begin_scope(name);
+    Ok(())
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    self.remaining.pop();
+    self.deep.end_scope();
+    Ok(())
+  }
+
+  fn respond(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    _response: &str,
+  ) -> Result<()> {
+    // Enums of a single variant don't request it
+    if kind == RequestKind::Synthetic && prompt == "variant" && self.is_deep() {
+      self.deep.choose(1)?;
+    }
+    Ok(())
+  }
+}
+
+impl PromptRequester for RandomPrompt {
+  fn is_interactive(&self) -> bool {
+    false
+  }
+
+  fn request(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    variants: &'static [&'static str],
+  ) -> Result<String> {
+    let label = base(prompt);
+    Ok(match (kind, label) {
+      (RequestKind::Question, "Add element?")
+      | (RequestKind::Question, "Add entry?") => yes_no(self.add_element(1)),
+      (RequestKind::Question, "Add byte?") => yes_no(self.add_element(0)),
+      (RequestKind::Question, "Some value?") => {
+        yes_no(!self.is_deep() && self.next_u64() & 1 == 1)
+      }
+      (_, "variant") if self.is_deep() && !variants.is_empty() => {
+        variants[self.deep.choose(variants.len())?].to_string()
+      }
+      (RequestKind::Datum, _) if !variants.is_empty() => {
+        variants[self.below(variants.len())].to_string()
+      }
+      (RequestKind::Datum, _) => self.datum(label),
+      // Accept any other question, such as confirmation
+      _ => variants.first().copied().unwrap_or("yes").to_string(),
+    })
+  }
+
+  fn report(&mut self, _kind: ReportKind, _msg: &str) -> Result<()> {
+    Ok(())
+  }
+}
+
+/// Prompt which takes each response from a slice of bytes, such as the input
+/// of a fuzzer.
+///
+/// Every choice of whether to add an element or a `Some` value takes one byte
+/// and is yes if that byte is odd. Variants take as many bytes as needed to
+/// index them, and numbers take their size in bytes, little-endian, so that
+/// floats may be infinite or NaN. Chars take four bytes, substituting U+FFFD
+/// for invalid ones, and strings take a byte for their length and then that
+/// many bytes, decoded as lossy UTF-8.
+///
+/// Once the bytes run out, every number is zero, every choice is no and every
+/// enum takes its first variant, except that an enum nested within itself
+/// takes its variants in turn as beyond the depth limit of `RandomPrompt`.
+/// Any slice therefore gives a valid value when used with `from_bare_prompt`,
+/// unless the type has no value which ends.
+pub struct BytesPrompt<'a> {
+  data: &'a [u8],
+  max_depth: usize,
+  depth: usize,
+  deep: DeepVariants,
+}
+
+impl<'a> BytesPrompt<'a> {
+  pub fn new(data: &'a [u8]) -> Self {
+    BytesPrompt {
+      data,
+      max_depth: 32,
+      depth: 0,
+      deep: DeepVariants::new(),
+    }
+  }
+
+  /// Sets the number of open scopes, including those of fields, beyond which
+  /// values stop growing. Defaults to 32.
+  pub fn set_max_depth(&mut self, max_depth: usize) {
+    self.max_depth = max_depth;
+  }
+
+  /// Returns the number of bytes not yet used.
+  pub fn remaining(&self) -> usize {
+    self.data.len()
+  }
+
+  fn is_deep(&self) -> bool {
+    self.depth >= self.max_depth
+  }
+
+  /// Takes up to `n` bytes as a little-endian number.
+  fn take(&mut self, n: usize) -> u128 {
+    let n = n.min(self.data.len());
+    let (bytes, rest) = self.data.split_at(n);
+    self.data = rest;
+    bytes
+      .iter()
+      .rev()
+      .fold(0, |acc, b| acc << 8 | u128::from(*b))
+  }
+
+  fn choose(&mut self, n: usize) -> usize {
+    let mut size = 1;
+    while size < 8 && (n - 1) >> (8 * size) > 0 {
+      size += 1;
+    }
+    (self.take(size) % n as u128) as usize
+  }
+
+  fn string(&mut self) -> String {
+    let len = self.take(1) as usize;
+    let len = len.min(self.data.len());
+    let (bytes, rest) = self.data.split_at(len);
+    self.data = rest;
+    String::from_utf8_lossy(bytes).into_owned()
+  }
+
+  fn datum(&mut self, label: &str) -> String {
+    match label {
+      "u8" => (self.take(1) as u8).to_string(),
+      "u16" => (self.take(2) as u16).to_string(),
+      "u32" => (self.take(4) as u32).to_string(),
+      "u64" => (self.take(8) as u64).to_string(),
+      "u128" => self.take(16).to_string(),
+      "i8" => (self.take(1) as i8).to_string(),
+      "i16" => (self.take(2) as i16).to_string(),
+      "i32" => (self.take(4) as i32).to_string(),
+      "i64" => (self.take(8) as i64).to_string(),
+      "i128" => (self.take(16) as i128).to_string(),
+      "f32" => f32::from_bits(self.take(4) as u32).to_string(),
+      "f64" => f64::from_bits(self.take(8) as u64).to_string(),
+      "char" => std::char::from_u32(self.take(4) as u32)
+        .unwrap_or('\u{fffd}')
+        .to_string(),
+      _ => self.string(),
+    }
+  }
+}
+
+impl<'a> PromptResponder for BytesPrompt<'a> {
+  fn begin_scope(&mut self, name: &str, _size: Option<usize>) -> Result<()> {
+    self.depth += 1;
+    self.deep.begin_scope(name);
+    Ok(())
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    self.depth -= 1;
+    self.deep.end_scope();
+    Ok(())
+  }
+
+  fn respond(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    _response: &str,
+  ) -> Result<()> {
+    // Enums of a single variant don't request it
+    let deep = self.is_deep() || self.data.is_empty();
+    if kind == RequestKind::Synthetic && prompt == "variant" && deep {
+      self.deep.choose(1)?;
+    }
+    Ok(())
+  }
+}
+
+impl<'a> PromptRequester for BytesPrompt<'a> {
+  fn is_interactive(&self) -> bool {
+    false
+  }
+
+  fn request(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    variants: &'static [&'static str],
+  ) -> Result<String> {
+    let label = base(prompt);
+    Ok(match (kind, label) {
+      (RequestKind::Question, "Add element?")
+      | (RequestKind::Question, "Add entry?")
+      | (RequestKind::Question, "Add byte?")
+      | (RequestKind::Question, "Some value?") => {
+        yes_no(!self.is_deep() && self.take(1) & 1 == 1)
+      }
+      // Once the bytes run out, variants are chosen as when deep
+      (_, "variant")
+        if (self.is_deep() || self.data.is_empty()) && !variants.is_empty() =>
+      {
+        variants[self.deep.choose(variants.len())?].to_string()
+      }
+      // Bools and enums, but not numbers which have variants
+      (RequestKind::Datum, "bool") | (RequestKind::Datum, "variant")
+        if !variants.is_empty() =>
+      {
+        variants[self.choose(variants.len())].to_string()
+      }
+      (RequestKind::Datum, _) => self.datum(label),
+      _ => variants.first().copied().unwrap_or("yes").to_string(),
+    })
+  }
+
+  fn report(&mut self, _kind: ReportKind, _msg: &str) -> Result<()> {
+    Ok(())
+  }
+}
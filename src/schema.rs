@@ -0,0 +1,273 @@
+use crate::error::Result;
+use crate::introspect::Shape;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Generates a JSON Schema for the JSON representation of `T`, from the shape
+/// found by `describe`.
+///
+/// Enums are taken to be externally tagged, and fields holding options aren't
+/// required. Structs may have other properties, as serde ignores unknown
+/// fields unless told otherwise. The keys of maps keyed by numbers, bools, chars or unit variants
+/// are restricted to those types' string forms.
+pub fn json_schema<T: DeserializeOwned>() -> Result<Value> {
+  Ok(shape_schema(&crate::describe::<T>()?))
+}
+
+/// Generates a JSON Schema for values of the given shape.
+///
+/// Types nested within themselves are placed under `$defs`, keyed by name,
+/// and referred to from within themselves. Distinct types of the same name
+/// are told apart by a numeric suffix.
+pub fn shape_schema(shape: &Shape) -> Value {
+  let mut targets = Vec::new();
+  find_recursive(shape, &mut Vec::new(), &mut targets);
+  let mut writer = SchemaWriter {
+    targets,
+    outer: Vec::new(),
+    keys: Vec::new(),
+    defs: Map::new(),
+  };
+  let mut schema = match writer.schema(shape) {
+    Value::Object(schema) => schema,
+    schema => {
+      let mut map = Map::new();
+      map.insert("allOf".to_string(), json!([schema]));
+      map
+    }
+  };
+  schema.insert("$schema".to_string(), json!(DRAFT));
+  if !writer.defs.is_empty() {
+    schema.insert("$defs".to_string(), Value::Object(writer.defs));
+  }
+  Value::Object(schema)
+}
+
+/// Returns the name of the type, if it's a named type which may contain
+/// other types.
+fn type_name(shape: &Shape) -> Option<&String> {
+  match shape {
+    Shape::Newtype(name, _)
+    | Shape::Struct(name, _)
+    | Shape::Tuple(name, _)
+    | Shape::Enum(name, _) => Some(name),
+    _ => None,
+  }
+}
+
+fn children(shape: &Shape) -> Vec<&Shape> {
+  match shape {
+    Shape::Newtype(_, s) | Shape::Option(s) | Shape::Seq(s) => vec![s],
+    Shape::Map(k, v) => vec![k, v],
+    Shape::Struct(_, fields) | Shape::Enum(_, fields) => {
+      fields.iter().map(|(_, s)| s).collect()
+    }
+    Shape::Tuple(_, elements) => elements.iter().collect(),
+    _ => Vec::new(),
+  }
+}
+
+/// Appends to `targets` the types referred to from within themselves, each
+/// being the nearest type enclosing a `Shape::Recursive` with its name.
+fn find_recursive<'a>(
+  shape: &'a Shape,
+  outer: &mut Vec<&'a Shape>,
+  targets: &mut Vec<&'a Shape>,
+) {
+  if let Shape::Recursive(name) = shape {
+    let target = outer.iter().rev().find(|o| type_name(o) == Some(name));
+    if let Some(target) = target {
+      if !targets.iter().any(|t| std::ptr::eq(*t, *target)) {
+        targets.push(target);
+      }
+    }
+    return;
+  }
+  outer.push(shape);
+  for child in children(shape) {
+    find_recursive(child, outer, targets);
+  }
+  outer.pop();
+}
+
+fn reference(key: &str) -> Value {
+  json!({ "$ref": format!("#/$defs/{}", key) })
+}
+
+fn primitive(label: &str) -> Value {
+  match label {
+    "bool" => json!({ "type": "boolean" }),
+    "u8" => json!({ "type": "integer", "minimum": 0, "maximum": u8::MAX }),
+    "u16" => json!({ "type": "integer", "minimum": 0, "maximum": u16::MAX }),
+    "u32" => json!({ "type": "integer", "minimum": 0, "maximum": u32::MAX }),
+    "u64" => json!({ "type": "integer", "minimum": 0, "maximum": u64::MAX }),
+    "u128" => json!({ "type": "integer", "minimum": 0 }),
+    "i8" => {
+      json!({ "type": "integer", "minimum": i8::MIN, "maximum": i8::MAX })
+    }
+    "i16" => {
+      json!({ "type": "integer", "minimum": i16::MIN, "maximum": i16::MAX })
+    }
+    "i32" => {
+      json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX })
+    }
+    "i64" => {
+      json!({ "type": "integer", "minimum": i64::MIN, "maximum": i64::MAX })
+    }
+    "i128" => json!({ "type": "integer" }),
+    "f32" | "f64" => json!({ "type": "number" }),
+    "char" => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+    _ => json!({ "type": "string" }),
+  }
+}
+
+struct SchemaWriter<'a> {
+  // The types referred to from within themselves
+  targets: Vec<&'a Shape>,
+  // The types enclosing the shape being written, and their keys if defined
+  outer: Vec<(&'a String, Option<String>)>,
+  // The key of each type defined, by shape
+  keys: Vec<(&'a Shape, String)>,
+  defs: Map<String, Value>,
+}
+
+impl<'a> SchemaWriter<'a> {
+  /// Returns the schema for `shape`, placing it under `$defs` if it's
+  /// referred to from within itself.
+  fn schema(&mut self, shape: &'a Shape) -> Value {
+    let name = match type_name(shape) {
+      Some(name) => name,
+      None => return self.inline(shape),
+    };
+    if !self.targets.iter().any(|t| std::ptr::eq(*t, shape)) {
+      self.outer.push((name, None));
+      let schema = self.inline(shape);
+      self.outer.pop();
+      return schema;
+    }
+    // The same type may be met more than once, and is only defined once
+    if let Some((_, key)) = self.keys.iter().find(|(s, _)| *s == shape) {
+      return reference(key);
+    }
+    let mut key = name.clone();
+    let mut n = 1;
+    while self.defs.contains_key(&key) {
+      n += 1;
+      key = format!("{}_{}", name, n);
+    }
+    // Reserve the key before recursing into the type
+    self.defs.insert(key.clone(), Value::Null);
+    self.keys.push((shape, key.clone()));
+    self.outer.push((name, Some(key.clone())));
+    let schema = self.inline(shape);
+    self.outer.pop();
+    self.defs.insert(key.clone(), schema);
+    reference(&key)
+  }
+
+  /// Returns the schema of the keys of a map, which are strings in JSON, if
+  /// it restricts them.
+  fn key_schema(&mut self, shape: &'a Shape) -> Option<Value> {
+    match shape {
+      Shape::Primitive(label) => match *label {
+        "u8" | "u16" | "u32" | "u64" | "u128" => {
+          Some(json!({ "pattern": "^[0-9]+$" }))
+        }
+        "i8" | "i16" | "i32" | "i64" | "i128" => {
+          Some(json!({ "pattern": "^-?[0-9]+$" }))
+        }
+        "f32" | "f64" => Some(json!({
+          "pattern": "^-?[0-9]+(\\.[0-9]+)?([eE][-+]?[0-9]+)?$"
+        })),
+        "bool" => Some(json!({ "enum": ["true", "false"] })),
+        "char" => Some(json!({ "minLength": 1, "maxLength": 1 })),
+        _ => None,
+      },
+      Shape::Newtype(_, s) => self.key_schema(s),
+      Shape::Enum(_, variants)
+        if variants.iter().all(|(_, s)| *s == Shape::Unit) =>
+      {
+        let names: Vec<&String> = variants.iter().map(|(v, _)| v).collect();
+        Some(json!({ "enum": names }))
+      }
+      _ => None,
+    }
+  }
+
+  fn inline(&mut self, shape: &'a Shape) -> Value {
+    match shape {
+      Shape::Primitive(label) => primitive(label),
+      Shape::Bytes => json!({ "type": "array", "items": primitive("u8") }),
+      Shape::Unit | Shape::UnitStruct(_) => json!({ "type": "null" }),
+      Shape::Newtype(_, s) => self.schema(s),
+      Shape::Struct(name, fields) => {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for (field, s) in fields {
+          properties.insert(field.clone(), self.schema(s));
+          if !matches!(s, Shape::Option(_)) {
+            required.push(json!(field));
+          }
+        }
+        json!({
+          "title": name,
+          "type": "object",
+          "properties": properties,
+          "required": required,
+        })
+      }
+      Shape::Tuple(_, elements) => {
+        let items: Vec<Value> =
+          elements.iter().map(|s| self.schema(s)).collect();
+        json!({
+          "type": "array",
+          "prefixItems": items,
+          "items": false,
+          "minItems": elements.len(),
+        })
+      }
+      Shape::Option(s) => {
+        json!({ "anyOf": [self.schema(s), { "type": "null" }] })
+      }
+      Shape::Seq(s) => json!({ "type": "array", "items": self.schema(s) }),
+      Shape::Map(k, v) => {
+        let mut schema = json!({
+          "type": "object",
+          "additionalProperties": self.schema(v),
+        });
+        if let Some(keys) = self.key_schema(k) {
+          schema["propertyNames"] = keys;
+        }
+        schema
+      }
+      Shape::Enum(name, variants) => {
+        let one_of: Vec<Value> = variants
+          .iter()
+          .map(|(variant, s)| match s {
+            Shape::Unit => json!({ "const": variant }),
+            s => {
+              let mut properties = Map::new();
+              properties.insert(variant.clone(), self.schema(s));
+              json!({
+                "type": "object",
+                "properties": properties,
+                "required": [variant],
+                "additionalProperties": false,
+              })
+            }
+          })
+          .collect();
+        json!({ "title": name, "oneOf": one_of })
+      }
+      Shape::Recursive(name) => {
+        match self.outer.iter().rev().find(|(n, _)| *n == name) {
+          Some((_, Some(key))) => reference(key),
+          _ => json!({}),
+        }
+      }
+      Shape::Unknown => json!({}),
+    }
+  }
+}
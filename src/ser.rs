@@ -1,5 +1,6 @@
 use serde::ser::{self, Serialize};
 
+use crate::de::{ADD_BYTE, ADD_ELEMENT, ADD_ENTRY, SOME_VALUE};
 use crate::error::{Error, Result};
 use crate::internal::{InternalPrompt, ScopeLimit};
 use crate::internal_prompt_responder_mixin;
@@ -67,16 +68,16 @@ impl<'a, P: PromptResponder> ser::Serializer for &'a mut Serializer<P> {
   fn serialize_bytes(self, v: &[u8]) -> Result<()> {
     self.begin_scope("bytes", None, ScopeLimit::Explicit)?;
     for byte in v {
-      self.respond(RequestKind::Question, "Add byte?", "yes")?;
+      self.respond(RequestKind::Question, ADD_BYTE, "yes")?;
       self.respond(RequestKind::Datum, "u8", &byte.to_string())?;
     }
-    self.respond(RequestKind::Question, "Add byte?", "no")?;
+    self.respond(RequestKind::Question, ADD_BYTE, "no")?;
     self.end_scope()
   }
 
   fn serialize_none(self) -> Result<()> {
     self.begin_scope("option", None, ScopeLimit::Explicit)?;
-    self.respond(RequestKind::Question, "Some value?", "no")?;
+    self.respond(RequestKind::Question, SOME_VALUE, "no")?;
     self.end_scope()
   }
 
@@ -85,7 +86,7 @@ impl<'a, P: PromptResponder> ser::Serializer for &'a mut Serializer<P> {
     T: ?Sized + Serialize,
   {
     self.begin_scope("option", None, ScopeLimit::Implicit)?;
-    self.respond(RequestKind::Question, "Some value?", "yes")?;
+    self.respond(RequestKind::Question, SOME_VALUE, "yes")?;
     value.serialize(self)
   }
 
@@ -226,7 +227,7 @@ impl<'a, P: PromptResponder> ser::SerializeSeq for Seq<'a, P> {
     self.index += 1;
     self
       .ser
-      .respond(RequestKind::Question, "Add element?", "yes")?;
+      .respond(RequestKind::Question, ADD_ELEMENT, "yes")?;
     value.serialize(&mut *self.ser)?;
     self.ser.end_scope()
   }
@@ -237,9 +238,7 @@ impl<'a, P: PromptResponder> ser::SerializeSeq for Seq<'a, P> {
       None,
       ScopeLimit::Explicit,
     )?;
-    self
-      .ser
-      .respond(RequestKind::Question, "Add element?", "no")?;
+    self.ser.respond(RequestKind::Question, ADD_ELEMENT, "no")?;
     self.ser.end_scope()?;
     self.ser.end_scope()
   }
@@ -345,7 +344,7 @@ impl<'a, P: PromptResponder> ser::SerializeMap for Map<'a, P> {
     self
       .ser
       .prompt
-      .respond(RequestKind::Question, "Add entry?", "yes")?;
+      .respond(RequestKind::Question, ADD_ENTRY, "yes")?;
     key.serialize(&mut *self.ser)
   }
 
@@ -363,9 +362,7 @@ impl<'a, P: PromptResponder> ser::SerializeMap for Map<'a, P> {
       None,
       ScopeLimit::Explicit,
     )?;
-    self
-      .ser
-      .respond(RequestKind::Question, "Add entry?", "no")?;
+    self.ser.respond(RequestKind::Question, ADD_ENTRY, "no")?;
     self.ser.end_scope()?;
     self.ser.end_scope()
   }
@@ -0,0 +1,132 @@
+use crate::error::{Error, Result};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+const HEADER: &str = "spaniel-session";
+
+fn lift_result<T>(value: io::Result<T>) -> Result<T> {
+  value.map_err(|e| Error::IoError(e.to_string()))
+}
+
+/// Escapes a response so that it occupies a single line.
+pub(crate) fn escape(s: &str) -> String {
+  let mut res = String::with_capacity(s.len());
+  for c in s.chars() {
+    match c {
+      '\\' => res.push_str("\\\\"),
+      '\n' => res.push_str("\\n"),
+      '\r' => res.push_str("\\r"),
+      c => res.push(c),
+    }
+  }
+  res
+}
+
+/// Reverses `escape`, returning `None` if `s` contains a bad escape. Braces
+/// may also be escaped, as transcripts do for a trailing one.
+pub(crate) fn unescape(s: &str) -> Option<String> {
+  let mut res = String::with_capacity(s.len());
+  let mut chars = s.chars();
+  while let Some(c) = chars.next() {
+    if c == '\\' {
+      match chars.next()? {
+        '\\' => res.push('\\'),
+        'n' => res.push('\n'),
+        'r' => res.push('\r'),
+        '{' => res.push('{'),
+        _ => return None,
+      }
+    } else {
+      res.push(c);
+    }
+  }
+  Some(res)
+}
+
+/// Reads the responses saved in a session file, if it exists.
+pub(crate) fn read_session(
+  path: &Path,
+  fingerprint: u64,
+) -> Result<Option<Vec<String>>> {
+  let file = match fs::File::open(path) {
+    Ok(file) => file,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+    Err(e) => return Err(Error::IoError(e.to_string())),
+  };
+  let mut lines = BufReader::new(file).lines();
+  let header = lift_result(lines.next().transpose())?.unwrap_or_default();
+  match header.split_once(' ') {
+    Some((HEADER, hash)) => {
+      if u64::from_str_radix(hash, 16) != Ok(fingerprint) {
+        return Err(Error::SessionMismatch);
+      }
+    }
+    _ => return Err(Error::IoError("Not a session file".to_string())),
+  }
+  let mut responses = Vec::new();
+  for line in lines {
+    match unescape(&lift_result(line)?) {
+      Some(response) => responses.push(response),
+      None => return Err(Error::IoError("Bad session file".to_string())),
+    }
+  }
+  Ok(Some(responses))
+}
+
+/// Writes responses to a session file.
+pub(crate) fn write_session(
+  path: &Path,
+  fingerprint: u64,
+  responses: &[String],
+) -> Result<()> {
+  let mut file = io::BufWriter::new(lift_result(fs::File::create(path))?);
+  lift_result(writeln!(file, "{} {:016x}", HEADER, fingerprint))?;
+  for response in responses {
+    lift_result(writeln!(file, "{}", escape(response)))?;
+  }
+  lift_result(file.flush())
+}
+
+/// Reads responses from a file containing one response per line.
+///
+/// Backslashes, line feeds, and carriage returns within responses are escaped
+/// as `\\`, `\n`, and `\r` respectively.
+pub fn read_responses<F: AsRef<Path>>(path: F) -> Result<Vec<String>> {
+  let file = lift_result(fs::File::open(path))?;
+  let mut responses = Vec::new();
+  for line in BufReader::new(file).lines() {
+    match unescape(&lift_result(line)?) {
+      Some(response) => responses.push(response),
+      None => return Err(Error::IoError("Bad responses file".to_string())),
+    }
+  }
+  Ok(responses)
+}
+
+/// Writes responses to a file in the format read by `read_responses`.
+pub fn write_responses<F: AsRef<Path>>(
+  path: F,
+  responses: &[String],
+) -> Result<()> {
+  let mut file = io::BufWriter::new(lift_result(fs::File::create(path))?);
+  for response in responses {
+    lift_result(writeln!(file, "{}", escape(response)))?;
+  }
+  lift_result(file.flush())
+}
+
+/// Writes a test fixture to a file.
+pub(crate) fn write_fixture(path: &Path, fixture: &str) -> Result<()> {
+  lift_result(fs::write(path, fixture))
+}
+
+/// Removes a session file if it exists.
+pub(crate) fn remove_session(path: &Path) -> Result<()> {
+  match fs::remove_file(path) {
+    Err(e) if e.kind() != io::ErrorKind::NotFound => {
+      Err(Error::IoError(e.to_string()))
+    }
+    _ => Ok(()),
+  }
+}
@@ -0,0 +1,203 @@
+use crate::error::{Error, Result};
+use crate::prompt::{
+  PromptRequester, PromptResponder, ReportKind, RequestKind,
+};
+use crate::util::from_bare_prompt;
+use serde::Deserialize;
+use std::collections::{BTreeSet, VecDeque};
+
+const MAX_DEPTH: usize = 64;
+// Bounds the work done for types which branch recursively
+const MAX_RUNS: usize = 256;
+
+/// Explores the answers to the requests made when deserialising a type, over
+/// repeated deserialisations called runs.
+///
+/// Each run makes the choices in its prefix and then the first choice for
+/// every later request. The first time a choice is met at a given path and
+/// prompt, runs making each of the other choices there are queued.
+pub(crate) struct Explorer {
+  seen: BTreeSet<(Vec<String>, String)>,
+  queue: VecDeque<Vec<usize>>,
+  runs: usize,
+}
+
+impl Explorer {
+  pub(crate) fn new() -> Self {
+    Explorer {
+      seen: BTreeSet::new(),
+      queue: VecDeque::from(vec![Vec::new()]),
+      runs: 0,
+    }
+  }
+
+  /// Starts the next run, if there is one.
+  pub(crate) fn next_run(&mut self) -> Option<Run<'_>> {
+    if self.runs == MAX_RUNS {
+      return None;
+    }
+    let prefix = self.queue.pop_front()?;
+    self.runs += 1;
+    Some(Run {
+      explorer: self,
+      path: Vec::new(),
+      prefix,
+      choices: Vec::new(),
+    })
+  }
+}
+
+/// The scopes open and the choices made so far in a run of an `Explorer`.
+pub(crate) struct Run<'a> {
+  explorer: &'a mut Explorer,
+  path: Vec<String>,
+  prefix: Vec<usize>,
+  choices: Vec<usize>,
+}
+
+impl<'a> Run<'a> {
+  /// Enters the scope `name`, failing once the scopes are too deep.
+  pub(crate) fn begin_scope(&mut self, name: &str) -> Result<()> {
+    // Elements of a collection share a key, so only the first is probed
+    let is_index = name.starts_with('[')
+      && name[1..]
+        .trim_end_matches(']')
+        .bytes()
+        .all(|b| b.is_ascii_digit());
+    self
+      .path
+      .push(if is_index { "[]" } else { name }.to_string());
+    if self.path.len() > MAX_DEPTH {
+      return Err(Error::BadResponse);
+    }
+    Ok(())
+  }
+
+  pub(crate) fn end_scope(&mut self) {
+    self.path.pop();
+  }
+
+  /// Returns a minimal response to a request, making a choice for questions
+  /// and variants.
+  pub(crate) fn answer(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    variants: &'static [&'static str],
+  ) -> String {
+    match (kind, prompt) {
+      (RequestKind::Question, _) => {
+        ["no", "yes"][self.choose(prompt, 2)].to_string()
+      }
+      (_, "variant") if !variants.is_empty() => {
+        variants[self.choose(prompt, variants.len())].to_string()
+      }
+      _ => variants.first().copied().unwrap_or("0").to_string(),
+    }
+  }
+
+  fn choose(&mut self, prompt: &str, count: usize) -> usize {
+    let pos = self.choices.len();
+    let choice = match self.prefix.get(pos) {
+      Some(choice) => *choice,
+      None => {
+        let key = (self.path.clone(), prompt.to_string());
+        if !self.explorer.seen.contains(&key) {
+          self.explorer.seen.insert(key);
+          for other in 1..count {
+            let mut choices = self.choices.clone();
+            choices.push(other);
+            self.explorer.queue.push_back(choices);
+          }
+        }
+        0
+      }
+    };
+    self.choices.push(choice);
+    choice
+  }
+}
+
+/// Prompt which gives minimal responses while hashing the requests made.
+struct FingerprintPrompt<'a> {
+  hash: &'a mut u64,
+  run: Run<'a>,
+}
+
+impl<'a> FingerprintPrompt<'a> {
+  fn write(&mut self, tag: u8, text: &str) {
+    // FNV-1a is used as it's stable across platforms and compiler versions
+    for byte in std::iter::once(tag).chain(text.bytes()).chain([0]) {
+      *self.hash ^= u64::from(byte);
+      *self.hash = self.hash.wrapping_mul(0x100000001b3);
+    }
+  }
+}
+
+impl<'a> PromptResponder for FingerprintPrompt<'a> {
+  fn begin_scope(&mut self, name: &str, size: Option<usize>) -> Result<()> {
+    self.write(b'{', name);
+    self.write(b'#', &format!("{:?}", size));
+    self.run.begin_scope(name)
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    self.write(b'}', "");
+    self.run.end_scope();
+    Ok(())
+  }
+
+  fn respond(
+    &mut self,
+    _kind: RequestKind,
+    prompt: &str,
+    response: &str,
+  ) -> Result<()> {
+    self.write(b'=', prompt);
+    self.write(b'=', response);
+    Ok(())
+  }
+}
+
+impl<'a> PromptRequester for FingerprintPrompt<'a> {
+  fn is_interactive(&self) -> bool {
+    false
+  }
+
+  fn request(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    variants: &'static [&'static str],
+  ) -> Result<String> {
+    self.write(b'?', prompt);
+    for variant in variants {
+      self.write(b'|', variant);
+    }
+    Ok(self.run.answer(kind, prompt, variants))
+  }
+
+  fn report(&mut self, _kind: ReportKind, _msg: &str) -> Result<()> {
+    Ok(())
+  }
+}
+
+/// Computes a fingerprint of the requests made when deserialising `T`.
+///
+/// Each collection and option is probed with one element, and each variant of
+/// each enum is followed, so the fingerprint covers the names and kinds of the
+/// fields which may be requested. Recursive types are only probed up to a
+/// fixed number of deserialisations.
+pub(crate) fn fingerprint<'de, T: Deserialize<'de>>() -> u64 {
+  let mut hash = 0xcbf29ce484222325;
+  let mut explorer = Explorer::new();
+  while let Some(run) = explorer.next_run() {
+    let prompt = FingerprintPrompt {
+      hash: &mut hash,
+      run,
+    };
+    // Only the requests made matter, not whether a value was produced
+    let _ = from_bare_prompt::<T, _>(prompt);
+  }
+  hash
+}
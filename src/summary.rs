@@ -0,0 +1,183 @@
+use serde::Serialize;
+
+use crate::document::{self, Build};
+use crate::error::Result;
+
+/// Tree of values making up a summary.
+enum Item {
+  Leaf(String),
+  None,
+  Seq(Vec<Item>),
+  Tuple(Vec<Item>),
+  Fields(Vec<(String, Item)>),
+  Variant(&'static str, Box<Item>),
+}
+
+impl Item {
+  /// Returns the item written on one line, if it is simple enough.
+  fn inline(&self) -> Option<String> {
+    let all = |items: &mut dyn Iterator<Item = &Item>| {
+      items.map(Item::inline).collect::<Option<Vec<String>>>()
+    };
+    match self {
+      Item::Leaf(value) => Some(value.clone()),
+      Item::None => Some("(none)".to_string()),
+      Item::Seq(elements) if elements.is_empty() => Some("[]".to_string()),
+      Item::Fields(fields) if fields.is_empty() => Some("{}".to_string()),
+      Item::Tuple(elements) => {
+        Some(format!("({})", all(&mut elements.iter())?.join(", ")))
+      }
+      Item::Variant(variant, inner) => match &**inner {
+        Item::Fields(fields) if fields.is_empty() => {
+          Some(format!("{} {{}}", variant))
+        }
+        Item::Fields(fields) => {
+          let values = all(&mut fields.iter().map(|(_, v)| v))?;
+          let fields: Vec<String> = fields
+            .iter()
+            .zip(values)
+            .map(|((k, _), v)| format!("{}: {}", k, v))
+            .collect();
+          Some(format!("{} {{ {} }}", variant, fields.join(", ")))
+        }
+        Item::Tuple(_) => Some(format!("{}{}", variant, inner.inline()?)),
+        inner => Some(format!("{}({})", variant, inner.inline()?)),
+      },
+      _ => None,
+    }
+  }
+
+  /// Returns the labelled children of a compound item.
+  fn children(&self) -> Vec<(String, &Item)> {
+    match self {
+      Item::Seq(elements) | Item::Tuple(elements) => elements
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (format!("[{}]", i), e))
+        .collect(),
+      Item::Fields(fields) => {
+        fields.iter().map(|(k, v)| (k.clone(), v)).collect()
+      }
+      Item::Variant(variant, inner) => vec![(variant.to_string(), &**inner)],
+      _ => Vec::new(),
+    }
+  }
+
+  fn render(
+    &self,
+    label: Option<&str>,
+    indent: usize,
+    lines: &mut Vec<String>,
+  ) {
+    let pad = "  ".repeat(indent);
+    let prefix = match label {
+      Some(label) => format!("{}{}: ", pad, label),
+      None => pad.clone(),
+    };
+    if let Some(value) = self.inline() {
+      lines.push(format!("{}{}", prefix, value));
+      return;
+    }
+    let indent = match (self, label) {
+      (Item::Variant(variant, _), _) => {
+        lines.push(format!("{}{} {{", prefix, variant));
+        indent + 1
+      }
+      (_, Some(_)) => {
+        lines.push(prefix.trim_end().to_string());
+        indent + 1
+      }
+      (_, None) => indent,
+    };
+    let children = match self {
+      Item::Variant(_, inner) => inner.children(),
+      _ => self.children(),
+    };
+    for (label, child) in children {
+      child.render(Some(&label), indent, lines);
+    }
+    if let Item::Variant(_, _) = self {
+      lines.push(format!("{}}}", pad));
+    }
+  }
+}
+
+impl Build for Item {
+  fn value(v: String) -> Self {
+    Item::Leaf(v)
+  }
+
+  fn bytes(v: &[u8]) -> Self {
+    Item::Leaf(format!("{:?}", v))
+  }
+
+  fn none() -> Self {
+    Item::None
+  }
+
+  fn unit() -> Self {
+    Item::Leaf("()".to_string())
+  }
+
+  fn unit_struct(name: &'static str) -> Self {
+    Item::Leaf(name.to_string())
+  }
+
+  fn unit_variant(variant: &'static str) -> Self {
+    Item::Leaf(variant.to_string())
+  }
+
+  fn variant(variant: &'static str, inner: Self) -> Self {
+    Item::Variant(variant, Box::new(inner))
+  }
+
+  fn seq(elements: Vec<Self>) -> Self {
+    Item::Seq(elements)
+  }
+
+  fn tuple(elements: Vec<Self>) -> Self {
+    Item::Tuple(elements)
+  }
+
+  fn map(entries: Vec<(Self, Self)>) -> Self {
+    let fields = entries
+      .into_iter()
+      .enumerate()
+      .map(|(i, (key, value))| match key.inline() {
+        Some(key) => (key, value),
+        // Keys which can't be written as labels are shown with their values
+        None => (
+          format!("[{}]", i),
+          Item::Fields(vec![
+            ("key".to_string(), key),
+            ("value".to_string(), value),
+          ]),
+        ),
+      })
+      .collect();
+    Item::Fields(fields)
+  }
+
+  fn fields(fields: Vec<(&'static str, Self)>) -> Self {
+    Item::Fields(
+      fields
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect(),
+    )
+  }
+}
+
+/// Summarises a value as lines of text for reviewing it.
+///
+/// Each field or map entry is written as `name: value`, and each element of a
+/// seq as `[index]: value`, with compound values nested beneath them. Options
+/// are written as their values or as `(none)`, and enums as `Variant`,
+/// `Variant(value)`, or `Variant { .. }`.
+pub(crate) fn summarise<T: Serialize + ?Sized>(
+  value: &T,
+) -> Result<Vec<String>> {
+  let mut lines = Vec::new();
+  document::build::<Item, _>(value)?.render(None, 0, &mut lines);
+  Ok(lines)
+}
@@ -0,0 +1,231 @@
+use crate::error::{Error, Result};
+use crate::prompt::{
+  PromptRequester, PromptResponder, ReportKind, RequestKind,
+};
+use crate::{from_bare_prompt, to_bare_prompt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// An event recorded by a `ScriptedPrompt`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LogEntry {
+  BeginScope(String, Option<usize>),
+  EndScope,
+  Response(RequestKind, String, &'static [&'static str], String),
+  Report(ReportKind, String),
+}
+
+/// Prompt which answers requests from a script of responses, recording
+/// everything sent to it.
+///
+/// The prompt isn't interactive unless made so by `with_interactive`, in which
+/// case the script may contain meta-commands such as `!undo` and `!restart`
+/// when used with `from_prompt`, and bad responses are reported and followed
+/// by the next response rather than failing. Requests made after the script
+/// has run out fail with `Error::OutOfResponses`.
+///
+/// A script made by `labelled` also gives the prompt of each request, and the
+/// prompt panics if a request is made with a different one.
+pub struct ScriptedPrompt {
+  responses: std::vec::IntoIter<(Option<String>, String)>,
+  log: Vec<LogEntry>,
+  interactive: bool,
+  level: usize,
+}
+
+impl ScriptedPrompt {
+  pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(responses: I) -> Self {
+    ScriptedPrompt {
+      responses: responses
+        .into_iter()
+        .map(|s| (None, s.into()))
+        .collect::<Vec<_>>()
+        .into_iter(),
+      log: Vec::new(),
+      interactive: false,
+      level: 0,
+    }
+  }
+
+  /// Makes a prompt which answers requests from a script of prompts and
+  /// responses, checking that each request is made with its prompt.
+  pub fn labelled<I, L, S>(script: I) -> Self
+  where
+    I: IntoIterator<Item = (L, S)>,
+    L: Into<String>,
+    S: Into<String>,
+  {
+    let mut prompt = ScriptedPrompt::new(Vec::<String>::new());
+    prompt.responses = script
+      .into_iter()
+      .map(|(l, s)| (Some(l.into()), s.into()))
+      .collect::<Vec<_>>()
+      .into_iter();
+    prompt
+  }
+
+  /// Makes the prompt interactive.
+  pub fn with_interactive(mut self) -> Self {
+    self.interactive = true;
+    self
+  }
+
+  /// Returns the events recorded so far.
+  pub fn log(&self) -> &[LogEntry] {
+    &self.log
+  }
+
+  /// Returns the events recorded.
+  pub fn into_log(self) -> Vec<LogEntry> {
+    self.log
+  }
+
+  /// Returns the non-synthetic responses recorded, whether sent to the prompt
+  /// or returned from it.
+  pub fn responses(&self) -> Vec<String> {
+    let mut rs = Vec::new();
+    for entry in self.log.iter() {
+      match entry {
+        LogEntry::Response(RequestKind::Synthetic, _, _, _) => {}
+        LogEntry::Response(_, _, _, s) => rs.push(s.clone()),
+        _ => {}
+      }
+    }
+    rs
+  }
+
+  /// Returns the names of the scopes begun.
+  pub fn scope_names(&self) -> Vec<String> {
+    let mut ns = Vec::new();
+    for entry in self.log.iter() {
+      if let LogEntry::BeginScope(s, _) = entry {
+        ns.push(s.clone());
+      }
+    }
+    ns
+  }
+
+  /// Returns the messages reported.
+  pub fn reports(&self) -> Vec<(ReportKind, String)> {
+    let mut rs = Vec::new();
+    for entry in self.log.iter() {
+      if let LogEntry::Report(kind, msg) = entry {
+        rs.push((*kind, msg.clone()));
+      }
+    }
+    rs
+  }
+
+  /// Returns the number of responses left in the script.
+  pub fn remaining(&self) -> usize {
+    self.responses.len()
+  }
+
+  /// Returns the number of scopes begun but not yet ended.
+  pub fn level(&self) -> usize {
+    self.level
+  }
+}
+
+impl PromptResponder for ScriptedPrompt {
+  fn begin_scope(&mut self, name: &str, size: Option<usize>) -> Result<()> {
+    self.level += 1;
+    self.log.push(LogEntry::BeginScope(name.to_string(), size));
+    Ok(())
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    self.level -= 1;
+    self.log.push(LogEntry::EndScope);
+    Ok(())
+  }
+
+  fn respond(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    response: &str,
+  ) -> Result<()> {
+    self.log.push(LogEntry::Response(
+      kind,
+      prompt.to_string(),
+      &[],
+      response.to_string(),
+    ));
+    Ok(())
+  }
+}
+
+impl PromptRequester for ScriptedPrompt {
+  fn is_interactive(&self) -> bool {
+    self.interactive
+  }
+
+  fn request(
+    &mut self,
+    kind: RequestKind,
+    prompt: &str,
+    variants: &'static [&'static str],
+  ) -> Result<String> {
+    let (label, s) = self.responses.next().ok_or(Error::OutOfResponses)?;
+    if let Some(label) = label {
+      assert_eq!(prompt, label, "prompt of request for {:?}", s);
+    }
+    self.log.push(LogEntry::Response(
+      kind,
+      prompt.to_string(),
+      variants,
+      s.clone(),
+    ));
+    Ok(s)
+  }
+
+  fn report(&mut self, kind: ReportKind, msg: &str) -> Result<()> {
+    self.log.push(LogEntry::Report(kind, msg.to_string()));
+    Ok(())
+  }
+}
+
+/// Asserts that serialising `value` gives `responses`, and that deserialising
+/// from `responses` gives `value` back, with the same scopes either way.
+///
+/// Both directions use the bare serialiser and deserialiser, so there is no
+/// confirmation. Panics if an assertion fails, or if serialising or
+/// deserialising fails.
+pub fn assert_round_trip<T>(value: &T, responses: &[&str])
+where
+  T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+  let mut ser_prompt = ScriptedPrompt::new(Vec::<String>::new());
+  to_bare_prompt(value, &mut ser_prompt).expect("serialising failed");
+  assert_eq!(ser_prompt.responses(), responses, "serialised responses");
+
+  let mut de_prompt = ScriptedPrompt::new(responses.iter().copied());
+  let res: T = from_bare_prompt(&mut de_prompt).expect("deserialising failed");
+  assert_eq!(&res, value, "deserialised value");
+  assert_eq!(de_prompt.remaining(), 0, "responses left over");
+  assert_eq!(
+    de_prompt.scope_names(),
+    ser_prompt.scope_names(),
+    "scope names"
+  );
+}
+
+/// Asserts that deserialising from `responses`, using `from_prompt` with an
+/// interactive `ScriptedPrompt`, gives `value` and uses every response.
+///
+/// The responses may include meta-commands, bad responses, and the answers to
+/// confirmation. Returns the log for further checks.
+pub fn assert_dialogue<T>(responses: &[&str], value: &T) -> Vec<LogEntry>
+where
+  T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+  let mut prompt =
+    ScriptedPrompt::new(responses.iter().copied()).with_interactive();
+  let res: T = crate::from_prompt(&mut prompt).expect("deserialising failed");
+  assert_eq!(&res, value, "deserialised value");
+  assert_eq!(prompt.remaining(), 0, "responses left over");
+  assert_eq!(prompt.level(), 0, "scopes left open");
+  prompt.into_log()
+}
@@ -0,0 +1,271 @@
+use crate::error::{Error, Result};
+use crate::prompt::{
+  PromptRequester, PromptResponder, ReportKind, RequestKind,
+};
+use crate::session::{escape, unescape};
+
+enum Event {
+  BeginScope(String),
+  EndScope,
+  // The whole line, as prompts may themselves contain ": "
+  Response(String),
+}
+
+impl Event {
+  fn describe(&self) -> String {
+    match self {
+      Event::BeginScope(name) => format!("'{} {{'", name),
+      Event::EndScope => "'}'".to_string(),
+      Event::Response(line) => {
+        let prompt = match line.split_once(": ") {
+          Some((prompt, _)) => prompt,
+          None => line.trim_end_matches(':'),
+        };
+        format!("'{}: ...'", prompt)
+      }
+    }
+  }
+}
+
+fn transcript_error(line: usize, msg: &str) -> Error {
+  Error::TranscriptError(format!("line {}: {}", line, msg))
+}
+
+/// Responder which writes a transcript of the responses it is sent.
+///
+/// The transcript has the same nested layout as the output of a
+/// `ReadWritePrompt` used as a responder, except that backslashes, line feeds,
+/// and carriage returns within responses are escaped as `\\`, `\n`, and `\r`
+/// respectively. A response ending with `{` has it escaped as `\{`, so that
+/// its line isn't taken for the start of a scope.
+#[derive(Default)]
+pub struct TranscriptWriter {
+  text: String,
+  level: usize,
+}
+
+impl TranscriptWriter {
+  pub fn new() -> Self {
+    TranscriptWriter::default()
+  }
+
+  /// Returns the transcript written so far.
+  pub fn into_string(self) -> String {
+    self.text
+  }
+
+  fn write_line(&mut self, line: &str) {
+    for _ in 0..self.level {
+      self.text.push_str("  ");
+    }
+    self.text.push_str(line);
+    self.text.push('\n');
+  }
+}
+
+impl PromptResponder for TranscriptWriter {
+  fn begin_scope(&mut self, name: &str, _size: Option<usize>) -> Result<()> {
+    self.write_line(&format!("{} {{", name));
+    self.level += 1;
+    Ok(())
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    self.level -= 1;
+    self.write_line("}");
+    Ok(())
+  }
+
+  fn respond(
+    &mut self,
+    _kind: RequestKind,
+    prompt: &str,
+    response: &str,
+  ) -> Result<()> {
+    let mut response = escape(response);
+    if response.ends_with('{') {
+      response.pop();
+      response.push_str("\\{");
+    }
+    self.write_line(&format!("{}: {}", prompt, response));
+    Ok(())
+  }
+}
+
+/// Prompt which answers requests from a transcript.
+///
+/// The scopes and prompts of the transcript are checked against those of the
+/// requests as they are made, failing with `Error::TranscriptError` if they
+/// don't match. Indentation is ignored, and so are blank lines. The prompt is
+/// not interactive, and bad responses also fail with `Error::TranscriptError`.
+///
+/// A scope may also be compacted into the names of the scopes and prompts
+/// within it, as `to_prompt` does for the scope of a newtype variant, in which
+/// case it has no lines of its own.
+pub struct TranscriptReader {
+  events: std::iter::Peekable<std::vec::IntoIter<(usize, Event)>>,
+  line: usize,
+  // The names of the scopes begun, and whether each was compacted
+  scopes: Vec<(String, bool)>,
+}
+
+impl TranscriptReader {
+  pub fn new(text: &str) -> Result<Self> {
+    let mut events = Vec::new();
+    let mut depth = 0usize;
+    for (i, line) in text.lines().enumerate() {
+      let line_no = i + 1;
+      let line = line.trim_start();
+      // Scope lines come first, as names may contain ": "
+      let event = if let Some(name) = line.strip_suffix(" {") {
+        depth += 1;
+        Event::BeginScope(name.to_string())
+      } else if line.trim_end() == "}" {
+        if depth == 0 {
+          return Err(transcript_error(line_no, "unmatched '}'"));
+        }
+        depth -= 1;
+        Event::EndScope
+      } else if line.trim_end().is_empty() {
+        continue;
+      } else if line.contains(':') {
+        Event::Response(line.to_string())
+      } else {
+        return Err(transcript_error(line_no, "expected scope or response"));
+      };
+      events.push((line_no, event));
+    }
+    if depth > 0 {
+      return Err(transcript_error(text.lines().count(), "unclosed scope"));
+    }
+    Ok(TranscriptReader {
+      events: events.into_iter().peekable(),
+      line: 0,
+      scopes: Vec::new(),
+    })
+  }
+
+  /// Fails if any of the transcript remains unread.
+  pub fn finish(&mut self) -> Result<()> {
+    match self.events.next() {
+      Some((line, event)) => Err(transcript_error(
+        line,
+        &format!("unexpected {}", event.describe()),
+      )),
+      None => Ok(()),
+    }
+  }
+
+  fn next_event(&mut self, expected: &str) -> Result<Event> {
+    match self.events.next() {
+      Some((line, event)) => {
+        self.line = line;
+        Ok(event)
+      }
+      None => Err(Error::TranscriptError(format!(
+        "expected {} at end of transcript",
+        expected
+      ))),
+    }
+  }
+
+  /// Returns `name` prefixed with the names of the compacted scopes it's
+  /// directly within.
+  fn compound_name(&self, name: &str) -> String {
+    let mut names: Vec<&str> = (self.scopes.iter().rev())
+      .take_while(|(_, compacted)| *compacted)
+      .map(|(name, _)| name.as_str())
+      .collect();
+    names.reverse();
+    names.push(name);
+    names.join(" -> ")
+  }
+
+  fn mismatch(&self, expected: &str, event: &Event) -> Error {
+    transcript_error(
+      self.line,
+      &format!("expected {}, found {}", expected, event.describe()),
+    )
+  }
+}
+
+impl PromptResponder for TranscriptReader {
+  fn begin_scope(&mut self, name: &str, _size: Option<usize>) -> Result<()> {
+    let full_name = self.compound_name(name);
+    let within = format!("{} -> ", full_name);
+    let compacted = match self.events.peek() {
+      Some((_, Event::BeginScope(line))) | Some((_, Event::Response(line))) => {
+        line.starts_with(&within)
+      }
+      _ => false,
+    };
+    if !compacted {
+      let expected = Event::BeginScope(full_name.clone()).describe();
+      match self.next_event(&expected)? {
+        Event::BeginScope(s) if s == full_name => {}
+        event => return Err(self.mismatch(&expected, &event)),
+      }
+    }
+    self.scopes.push((name.to_string(), compacted));
+    Ok(())
+  }
+
+  fn end_scope(&mut self) -> Result<()> {
+    if let Some((_, true)) = self.scopes.pop() {
+      return Ok(());
+    }
+    let expected = Event::EndScope.describe();
+    match self.next_event(&expected)? {
+      Event::EndScope => Ok(()),
+      event => Err(self.mismatch(&expected, &event)),
+    }
+  }
+
+  fn respond(
+    &mut self,
+    _kind: RequestKind,
+    _prompt: &str,
+    _response: &str,
+  ) -> Result<()> {
+    Ok(())
+  }
+}
+
+impl PromptRequester for TranscriptReader {
+  fn is_interactive(&self) -> bool {
+    false
+  }
+
+  fn request(
+    &mut self,
+    _kind: RequestKind,
+    prompt: &str,
+    _variants: &'static [&'static str],
+  ) -> Result<String> {
+    let prompt = &self.compound_name(prompt);
+    let expected = format!("'{}: ...'", prompt);
+    let event = self.next_event(&expected)?;
+    let response = match &event {
+      Event::Response(line) => match line.strip_prefix(prompt) {
+        Some(":") => Some(""),
+        Some(rest) => rest.strip_prefix(": "),
+        None => None,
+      },
+      _ => None,
+    };
+    match response {
+      Some(response) => match unescape(response) {
+        Some(response) => Ok(response),
+        None => Err(transcript_error(self.line, "bad escape")),
+      },
+      None => Err(self.mismatch(&expected, &event)),
+    }
+  }
+
+  fn report(&mut self, kind: ReportKind, msg: &str) -> Result<()> {
+    match kind {
+      ReportKind::BadResponse => Err(transcript_error(self.line, msg)),
+      ReportKind::Help => Ok(()),
+    }
+  }
+}
@@ -1,11 +1,21 @@
 use crate::de::Deserializer;
+use crate::diff::{self, Change};
+use crate::document::{self, DocumentPrompt};
 use crate::error::{Error, Result, UserAction};
+use crate::flat::{self, ArgsPrompt, EnvPrompt};
+use crate::introspect::{self, Shape};
+use crate::path::{PathEntry, PathRecorder, ScopePath};
 use crate::prompt::{
   CompactPrompt, MetaCommandPrompt, PromptRequester, PromptResponder,
-  ReplayPrompt, ReportKind, RequestKind,
+  ReplayPrompt, ReportKind, RequestKind, ResponsesPrompt,
 };
 use crate::ser::Serializer;
+use crate::transcript::{TranscriptReader, TranscriptWriter};
+use crate::{session, shape, summary};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
 
 /// Deserialise a value of type `T` from a prompt using the bare deserialiser.
 pub fn from_bare_prompt<'de, T: Deserialize<'de>, P: PromptRequester>(
@@ -33,6 +43,73 @@ pub(crate) fn ask_yes_no<P: PromptRequester>(
   }
 }
 
+fn confirm<T, P: PromptRequester>(_value: &T, prompt: &mut P) -> Result<()> {
+  if ask_yes_no(prompt, "Accept value?")? {
+    Ok(())
+  } else {
+    Err(Error::UserAction(UserAction::Restart(0)))
+  }
+}
+
+fn review<T: Serialize, P: PromptRequester>(
+  value: &T,
+  prompt: &mut P,
+) -> Result<()> {
+  for line in summary::summarise(value)? {
+    prompt.report(ReportKind::Help, &line)?;
+  }
+  if ask_yes_no(prompt, "Accept value?")? {
+    return Ok(());
+  }
+  let mut recorder = PathRecorder::new();
+  to_bare_prompt(value, &mut recorder)?;
+  let data: Vec<&PathEntry> = recorder
+    .entries()
+    .iter()
+    .filter(|e| e.kind == RequestKind::Datum)
+    .collect();
+  for (i, entry) in data.iter().enumerate() {
+    let name = if entry.path.is_empty() {
+      entry.prompt.clone()
+    } else {
+      entry.path.to_string()
+    };
+    prompt.report(
+      ReportKind::Help,
+      &format!("#{} {}: {}", i + 1, name, entry.response),
+    )?;
+  }
+  loop {
+    let s = prompt.request(RequestKind::Question, "Revise field?", &[])?;
+    let path = if s.is_empty() {
+      Some(ScopePath::new())
+    } else if let Ok(n) = usize::from_str(&s) {
+      n.checked_sub(1)
+        .and_then(|i| data.get(i))
+        .map(|e| e.path.clone())
+    } else {
+      let pattern = ScopePath::from_str(&s).unwrap();
+      data
+        .iter()
+        .find_map(|e| e.path.find(&pattern).map(|len| e.path.prefix(len)))
+    };
+    match path {
+      Some(path) if path.is_empty() => {
+        return Err(Error::UserAction(UserAction::Restart(0)))
+      }
+      Some(path) => {
+        return Err(Error::UserAction(UserAction::Revise(path.to_string())))
+      }
+      None => {
+        prompt.report(ReportKind::BadResponse, "No such field")?;
+      }
+    }
+    if !prompt.is_interactive() {
+      return Err(Error::BadResponse);
+    }
+  }
+}
+
 /// Deserialise a value of type `T` from a prompt using the bare deserialiser
 /// followed by confirmation.
 pub fn from_bare_prompt_confirm<
@@ -43,28 +120,63 @@ pub fn from_bare_prompt_confirm<
   mut prompt: P,
 ) -> Result<T> {
   let res = from_bare_prompt(&mut prompt)?;
-  if ask_yes_no(&mut prompt, "Accept value?")? {
-    Ok(res)
-  } else {
-    Err(Error::UserAction(UserAction::Restart(0)))
-  }
+  confirm(&res, &mut prompt)?;
+  Ok(res)
 }
 
 /// Deserialise a value of type `T` from a prompt while handling undos and
 /// restarts.
+///
+/// Responses are replayed quietly so that the dialogue up to the point being
+/// returned to isn't repeated each time.
 pub fn from_replay_prompt<'de, T: Deserialize<'de>, P: PromptRequester>(
   prompt: P,
 ) -> Result<T> {
   let mut replay = ReplayPrompt::new(prompt);
+  replay.set_quiet(true);
   replay.record();
+  replay_loop(&mut replay, confirm)
+}
+
+/// Runs the deserialiser until a value is accepted by `accept`, handling the
+/// errors which the user may recover from.
+fn replay_loop<'de, T, P, F>(
+  replay: &mut ReplayPrompt<P>,
+  mut accept: F,
+) -> Result<T>
+where
+  T: Deserialize<'de>,
+  P: PromptRequester,
+  F: FnMut(&T, &mut ReplayPrompt<P>) -> Result<()>,
+{
   loop {
-    match from_bare_prompt_confirm(&mut replay) {
+    let res = from_bare_prompt(&mut *replay).and_then(|value| {
+      // Discard any responses to the confirmation from the log
+      let mark = replay.position();
+      let res = accept(&value, &mut *replay);
+      replay.discard_from(mark);
+      res.map(|()| value)
+    });
+    match res {
       Ok(s) => return Ok(s),
-      Err(Error::SerdeError(msg)) if replay.is_interactive() => {
-        // Assume Serde error is caused by malformed input and undo 1 step
+      Err(Error::SerdeError(msg, None)) if replay.is_interactive() => {
+        // Assume Serde error is caused by malformed input, reporting it as a
+        // bad response removes the last response from the log
         replay
           .report(ReportKind::BadResponse, &format!("Serde Error: {}", msg))?;
-        replay.undo(1);
+        replay.replay()?;
+      }
+      Err(Error::SerdeError(msg, Some(path))) if replay.is_interactive() => {
+        // Back up to the start of the scope in which the error surfaced
+        let start = replay.scope_start(&path);
+        replay.report(
+          ReportKind::BadResponse,
+          &format!("Serde Error: {} (at {})", msg, path),
+        )?;
+        // The rejected responses mustn't be brought back by a redo
+        if let Some(n) = start {
+          replay.discard_from(n);
+        }
         replay.replay()?;
       }
       Err(Error::UserAction(UserAction::Undo(n)))
@@ -73,12 +185,28 @@ pub fn from_replay_prompt<'de, T: Deserialize<'de>, P: PromptRequester>(
         replay.undo(n);
         replay.replay()?;
       }
+      Err(Error::UserAction(UserAction::Redo(n)))
+        if replay.is_interactive() =>
+      {
+        if replay.redo(n) == 0 {
+          replay.report(ReportKind::Help, "Nothing to redo")?;
+        }
+        replay.replay()?;
+      }
       Err(Error::UserAction(UserAction::Restart(n)))
         if replay.is_interactive() =>
       {
         replay.restart_from(n);
         replay.replay()?;
       }
+      Err(Error::UserAction(UserAction::Revise(path)))
+        if replay.is_interactive() =>
+      {
+        if !replay.revise(&path) {
+          replay.report(ReportKind::Help, "Nothing to revise")?;
+        }
+        replay.replay()?;
+      }
       Err(e) => return Err(e),
     }
   }
@@ -92,6 +220,269 @@ pub fn from_prompt<'de, T: Deserialize<'de>, P: PromptRequester>(
   from_replay_prompt(MetaCommandPrompt::new(CompactPrompt::new(prompt)))
 }
 
+/// Deserialise a value of type `T` from a prompt like `from_prompt`, with a
+/// review of the value in place of the plain confirmation.
+///
+/// The value is summarised as by `to_summary_string` before the user is asked
+/// to accept it. If the user declines, its fields are listed with numbers, and
+/// they may enter the number or path of a field to revise, after which the
+/// responses from that field on are asked again, or nothing to restart from
+/// the beginning.
+pub fn from_prompt_review<
+  'de,
+  T: Deserialize<'de> + Serialize,
+  P: PromptRequester,
+>(
+  prompt: P,
+) -> Result<T> {
+  let mut replay =
+    ReplayPrompt::new(MetaCommandPrompt::new(CompactPrompt::new(prompt)));
+  replay.set_quiet(true);
+  replay.record();
+  replay_loop(&mut replay, review)
+}
+
+/// Deserialise a value of type `T` from a prompt like `from_prompt_review`,
+/// resuming from the session saved in the file at `path` if there is one.
+///
+/// If deserialising fails, for example because the user cancelled, the
+/// responses given so far are saved to the file along with a fingerprint of
+/// the shape of `T`. The file is removed once a value has been accepted.
+/// Resuming from a session saved for a type of a different shape fails with
+/// `Error::SessionMismatch`.
+pub fn from_prompt_resume<
+  'de,
+  T: Deserialize<'de> + Serialize,
+  P: PromptRequester,
+  F: AsRef<Path>,
+>(
+  prompt: P,
+  path: F,
+) -> Result<T> {
+  let path = path.as_ref();
+  let fingerprint = shape::fingerprint::<T>();
+  let mut replay =
+    ReplayPrompt::new(MetaCommandPrompt::new(CompactPrompt::new(prompt)));
+  replay.set_quiet(true);
+  replay.record();
+  if let Some(responses) = session::read_session(path, fingerprint)? {
+    replay.load(responses);
+    replay.replay()?;
+  }
+  match replay_loop(&mut replay, review) {
+    Ok(value) => {
+      session::remove_session(path)?;
+      Ok(value)
+    }
+    Err(e) => {
+      // Keep any responses which failed before they could be replayed
+      session::write_session(path, fingerprint, &replay.all_responses())?;
+      Err(e)
+    }
+  }
+}
+
+/// Deserialise a value of type `T` from a prompt like `from_prompt_review`,
+/// writing a test reproducing the session to the file at `path` once a value
+/// has been accepted.
+///
+/// The deserialised type is named in the test by `type_path`, which should be
+/// a path to it from the crate in which the test is to be placed. See
+/// `to_test_fixture` for the form of the test.
+pub fn from_prompt_export<
+  'de,
+  T: Deserialize<'de> + Serialize,
+  P: PromptRequester,
+  F: AsRef<Path>,
+>(
+  prompt: P,
+  path: F,
+  type_path: &str,
+) -> Result<T> {
+  let mut replay =
+    ReplayPrompt::new(MetaCommandPrompt::new(CompactPrompt::new(prompt)));
+  replay.set_quiet(true);
+  replay.record();
+  let value = replay_loop(&mut replay, review)?;
+  let script = replay.labelled_responses();
+  let fixture = to_test_fixture(type_path, &script, &value)?;
+  session::write_fixture(path.as_ref(), &fixture)?;
+  Ok(value)
+}
+
+/// Returns the source of a Rust test which checks that deserialising a value
+/// of the type at `type_path` from `script` gives `value`.
+///
+/// The script is a list of prompts and the responses given to them. The test
+/// answers requests with a labelled `ScriptedPrompt`, which requires the
+/// `testing` feature and checks that each request is made with its prompt,
+/// and compares the transcript of the value deserialised with that of
+/// `value`.
+pub fn to_test_fixture<T: Serialize>(
+  type_path: &str,
+  script: &[(String, String)],
+  value: &T,
+) -> Result<String> {
+  let mut out = String::new();
+  out.push_str("#[test]\nfn spaniel_session() {\n");
+  out.push_str("  use serde_spaniel::testing::ScriptedPrompt;\n\n");
+  out.push_str("  let mut prompt = ScriptedPrompt::labelled(vec![\n");
+  for (label, response) in script {
+    out.push_str(&format!("    ({:?}, {:?}),\n", label, response));
+  }
+  out.push_str("  ]);\n");
+  out.push_str(&format!("  let value: {} =\n", type_path));
+  out.push_str("    serde_spaniel::from_bare_prompt(&mut prompt).unwrap();\n");
+  out.push_str("  assert_eq!(prompt.remaining(), 0);\n");
+  out.push_str("  assert_eq!(\n");
+  out.push_str("    serde_spaniel::to_transcript_string(&value).unwrap(),\n");
+  out.push_str("    concat!(\n");
+  for line in to_transcript_string(value)?.lines() {
+    out.push_str(&format!("      {:?},\n", format!("{}\n", line)));
+  }
+  out.push_str("    )\n  );\n}\n");
+  Ok(out)
+}
+
+/// Deserialise a value of type `T` from responses such as those produced by
+/// `to_responses`.
+///
+/// Fails with `Error::OutOfResponses` if the responses run out.
+pub fn from_responses<
+  'de,
+  T: Deserialize<'de>,
+  I: IntoIterator<Item = String>,
+>(
+  responses: I,
+) -> Result<T> {
+  from_bare_prompt(ResponsesPrompt::new(responses))
+}
+
+/// Deserialise a value of type `T` from responses like `from_responses`,
+/// continuing from a prompt like `from_prompt_review` if the responses run out.
+pub fn from_responses_or_prompt<
+  'de,
+  T: Deserialize<'de> + Serialize,
+  I: IntoIterator<Item = String>,
+  P: PromptRequester,
+>(
+  responses: I,
+  prompt: P,
+) -> Result<T> {
+  let mut replay =
+    ReplayPrompt::new(MetaCommandPrompt::new(CompactPrompt::new(prompt)));
+  replay.set_quiet(true);
+  replay.record();
+  replay.load(responses);
+  replay.replay()?;
+  replay_loop(&mut replay, review)
+}
+
+/// Deserialise a value of type `T` from a document like `from_prompt_review`,
+/// answering requests from the document where possible.
+///
+/// The document may be any serialisable value, such as a JSON or TOML value.
+/// Requests which the document can't answer, including the confirmation, are
+/// passed to the prompt. See `DocumentPrompt` for details. Use
+/// `from_bare_prompt` with a `DocumentPrompt` to deserialise without
+/// confirmation.
+pub fn from_document<
+  'de,
+  T: Deserialize<'de> + Serialize,
+  D: Serialize + ?Sized,
+  P: PromptRequester,
+>(
+  document: &D,
+  prompt: P,
+) -> Result<T> {
+  let inner = MetaCommandPrompt::new(CompactPrompt::new(prompt));
+  let mut replay = ReplayPrompt::new(DocumentPrompt::new(document, inner)?);
+  replay.set_quiet(true);
+  replay.record();
+  replay_loop(&mut replay, review)
+}
+
+/// Deserialise a value of type `T` like `from_prompt_review`, answering
+/// requests from the environment variables beginning with `prefix` where
+/// possible.
+///
+/// Requests which aren't answered by a variable, including the confirmation,
+/// are passed to the prompt. Once a value has been accepted, any variables
+/// beginning with `prefix` which weren't used are reported. See `EnvPrompt`
+/// for details.
+pub fn from_env<'de, T: Deserialize<'de> + Serialize, P: PromptRequester>(
+  prefix: &str,
+  prompt: P,
+) -> Result<T> {
+  let inner = MetaCommandPrompt::new(CompactPrompt::new(prompt));
+  let mut env = EnvPrompt::new(prefix, inner);
+  let mut replay = ReplayPrompt::new(&mut env);
+  replay.set_quiet(true);
+  replay.record();
+  let value = replay_loop(&mut replay, review)?;
+  env.report_unused()?;
+  Ok(value)
+}
+
+/// Deserialise a value of type `T` like `from_prompt_review`, answering
+/// requests from command-line arguments of the form `--<path>=<value>` where
+/// possible.
+///
+/// Requests which aren't answered by an argument, including the confirmation,
+/// are passed to the prompt. Once a value has been accepted, any arguments
+/// which weren't used are reported. See `ArgsPrompt` for details.
+pub fn from_args<
+  'de,
+  T: Deserialize<'de> + Serialize,
+  I: IntoIterator<Item = String>,
+  P: PromptRequester,
+>(
+  args: I,
+  prompt: P,
+) -> Result<T> {
+  let inner = MetaCommandPrompt::new(CompactPrompt::new(prompt));
+  let mut args = ArgsPrompt::new(args, inner);
+  let mut replay = ReplayPrompt::new(&mut args);
+  replay.set_quiet(true);
+  replay.record();
+  let value = replay_loop(&mut replay, review)?;
+  args.report_unused()?;
+  Ok(value)
+}
+
+/// Deserialise a value of type `T` from a transcript such as one produced by
+/// `to_transcript_string`.
+///
+/// This isn't a `serde::Deserializer` of its own. The transcript is read by a
+/// `TranscriptReader`, which answers the requests of the usual deserialiser
+/// like any other prompt. The scope names and prompts in the transcript must
+/// match those of the requests made while deserialising, otherwise this fails
+/// with `Error::TranscriptError`.
+pub fn from_transcript_str<'de, T: Deserialize<'de>>(s: &str) -> Result<T> {
+  let mut reader = TranscriptReader::new(s)?;
+  let value =
+    from_bare_prompt(MetaCommandPrompt::new(CompactPrompt::new(&mut reader)))?;
+  reader.finish()?;
+  Ok(value)
+}
+
+/// Deserialise a value of type `T` from lines of the form `<path>=<value>`,
+/// such as those produced by `to_flat_string`.
+///
+/// Blank lines and lines beginning with `#` are ignored, as are lines whose
+/// paths don't correspond to a value of type `T`. Fails with
+/// `Error::FlatError` if a line can't be parsed, or `Error::OutOfResponses` if
+/// a value is missing.
+pub fn from_flat_str<'de, T: Deserialize<'de>>(s: &str) -> Result<T> {
+  let root = flat::parse_flat(s)?;
+  let none = std::iter::empty::<String>();
+  from_bare_prompt(DocumentPrompt::from_node(
+    root,
+    false,
+    ResponsesPrompt::new(none),
+  ))
+}
+
 /// Deserialise an instance of type `T` from the console.
 pub fn from_console<'de, T: Deserialize<'de>>() -> Result<T> {
   #[cfg(feature = "rustyline")]
@@ -118,6 +509,103 @@ pub fn to_bare_prompt<T: Serialize, P: PromptResponder>(
   Serialize::serialize(value, &mut Serializer::from_prompt(prompt))
 }
 
+/// Serialise an instance of type `T` to the responses which would be given to
+/// deserialise it, in the order in which they would be requested.
+///
+/// Since the serialiser doesn't know how many variants an enum has, values
+/// containing enums with only one variant can't be deserialised from the
+/// responses.
+pub fn to_responses<T: Serialize>(value: &T) -> Result<Vec<String>> {
+  let mut recorder = PathRecorder::new();
+  to_bare_prompt(value, &mut recorder)?;
+  Ok(
+    recorder
+      .entries()
+      .iter()
+      .map(|e| e.response.clone())
+      .collect(),
+  )
+}
+
+/// Describes what would be requested to deserialise an instance of type `T`,
+/// without user input.
+///
+/// The type is deserialised several times by a prompt which follows every
+/// variant of each enum, a `Some` value for each option, and a single element
+/// for each seq and map, and the shape is made from the scopes and requests
+/// met. A type nested within a type of the same name is described as
+/// `Shape::Recursive` if its shape matches the outer one, so that generic
+/// types such as `W<W<u8>>` are described in full. Fails only if nothing
+/// could be discovered, such as when the type rejects every probe value.
+pub fn describe<T: DeserializeOwned>() -> Result<Shape> {
+  introspect::describe::<T>()
+}
+
+/// Compares two instances of type `T`, returning the differences between
+/// the responses which would be given to deserialise them.
+///
+/// Responses are aligned by their scope paths, so added and removed seq
+/// elements are those at the end. Map entries are aligned by their keys where
+/// the keys are single values, and appear in paths as `[key]` rather than by
+/// index. Use `diff::write_diff` to show the changes on a responder.
+pub fn diff_values<T: Serialize + ?Sized>(
+  old: &T,
+  new: &T,
+) -> Result<Vec<Change>> {
+  let mut old_recorder = PathRecorder::new();
+  Serialize::serialize(old, &mut Serializer::from_prompt(&mut old_recorder))?;
+  let mut new_recorder = PathRecorder::new();
+  Serialize::serialize(new, &mut Serializer::from_prompt(&mut new_recorder))?;
+  Ok(diff::diff_entries(
+    old_recorder.entries(),
+    new_recorder.entries(),
+  ))
+}
+
+/// Serialise an instance of type `T` to a transcript of the responses which
+/// would be given to deserialise it, in the format written by `to_prompt` to a
+/// `ReadWritePrompt`.
+///
+/// As with `to_responses`, values containing enums with only one variant
+/// can't be deserialised from the transcript.
+pub fn to_transcript_string<T: Serialize>(value: &T) -> Result<String> {
+  let mut writer = TranscriptWriter::new();
+  to_prompt(value, &mut writer)?;
+  Ok(writer.into_string())
+}
+
+/// Serialise an instance of type `T` to lines of the form `<path>=<value>`,
+/// one for each value it contains.
+///
+/// Paths are made up of field names, map keys, enum variant names, and
+/// indices, separated by full stops, as in `children.0.name=Alice`. Keys and
+/// values which couldn't otherwise be read back unchanged are quoted with
+/// `"`, with quotes, backslashes, line feeds, carriage returns, and tabs
+/// escaped as `\"`, `\\`, `\n`, `\r`, and `\t` respectively, and numeric map
+/// keys are quoted to tell them from indices. Missing values such as `None`
+/// are written with nothing after the `=`, and empty seqs and maps as `[]` and
+/// `{}`. Map entries whose keys aren't plain values are left out.
+pub fn to_flat_string<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+  let mut out = String::new();
+  flat::write_flat(&document::to_node(value)?, &mut Vec::new(), &mut out);
+  Ok(out)
+}
+
+/// Serialise an instance of type `T` to a summary for reviewing it, with one
+/// line for each field, element, or entry.
+///
+/// Unlike the output of `to_prompt`, questions and units are left out. Seqs
+/// are shown as lists of `[index]: value` lines, options as their values or
+/// `(none)`, and enums as `Variant`, `Variant(value)`, or `Variant { .. }`.
+pub fn to_summary_string<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+  let mut out = String::new();
+  for line in summary::summarise(value)? {
+    out.push_str(&line);
+    out.push('\n');
+  }
+  Ok(out)
+}
+
 /// Serialise an instance of type `T` to a prompt while handling meta-commands
 /// and scope compacting.
 pub fn to_prompt<T: Serialize, P: PromptResponder>(
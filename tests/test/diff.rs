@@ -0,0 +1,103 @@
+use serde::Serialize;
+use serde_spaniel::diff::{write_diff, Change};
+use serde_spaniel::path::ScopePath;
+use serde_spaniel::stdio::ReadWritePrompt;
+use serde_spaniel::*;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+#[derive(Serialize)]
+struct Config {
+  name: String,
+  tags: Vec<String>,
+  limits: BTreeMap<String, u32>,
+}
+
+fn path(s: &str) -> ScopePath {
+  ScopePath::from_str(s).unwrap()
+}
+
+fn config(name: &str, tags: &[&str], limits: &[(&str, u32)]) -> Config {
+  Config {
+    name: name.into(),
+    tags: tags.iter().map(|t| t.to_string()).collect(),
+    limits: limits.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+  }
+}
+
+#[test]
+fn identical_values() {
+  let value = config("a", &["x"], &[("cpu", 1)]);
+  assert_eq!(diff_values(&value, &value).unwrap(), vec![]);
+}
+
+#[test]
+fn changed_fields_and_entries() {
+  let old = config("a", &["x", "y"], &[("cpu", 1), ("mem", 2)]);
+  let new = config("b", &["x"], &[("disk", 3), ("mem", 4)]);
+  let changes = diff_values(&old, &new).unwrap();
+  assert_eq!(
+    changes,
+    vec![
+      Change::Changed {
+        path: path("Config.name"),
+        prompt: "string".into(),
+        old: "a".into(),
+        new: "b".into(),
+      },
+      Change::Removed {
+        path: path("Config.tags.seq[1]"),
+        prompt: "string".into(),
+        value: "y".into(),
+      },
+      Change::Removed {
+        path: path("Config.limits.map[cpu]"),
+        prompt: "string".into(),
+        value: "cpu".into(),
+      },
+      Change::Removed {
+        path: path("Config.limits.map[cpu]"),
+        prompt: "u32".into(),
+        value: "1".into(),
+      },
+      Change::Added {
+        path: path("Config.limits.map[disk]"),
+        prompt: "string".into(),
+        value: "disk".into(),
+      },
+      Change::Added {
+        path: path("Config.limits.map[disk]"),
+        prompt: "u32".into(),
+        value: "3".into(),
+      },
+      Change::Changed {
+        path: path("Config.limits.map[mem]"),
+        prompt: "u32".into(),
+        old: "2".into(),
+        new: "4".into(),
+      },
+    ]
+  );
+  let mut output = Vec::new();
+  write_diff(
+    &changes[..2],
+    ReadWritePrompt::new_responder(&mut output),
+    false,
+  )
+  .unwrap();
+  assert_eq!(
+    String::from_utf8(output).unwrap(),
+    "Config {\n  name {\n    -string: a\n    +string: b\n  }\n  tags {\n    \
+     seq {\n      [1] {\n        -string: y\n      }\n    }\n  }\n}\n"
+  );
+  let mut output = Vec::new();
+  write_diff(
+    &changes[..1],
+    ReadWritePrompt::new_responder(&mut output),
+    true,
+  )
+  .unwrap();
+  assert!(String::from_utf8(output)
+    .unwrap()
+    .contains("\x1b[31m-string: a\x1b[0m\n"));
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_spaniel::document::DocumentPrompt;
+use serde_spaniel::*;
+use std::collections::BTreeMap;
+use std::iter::empty;
+
+use super::mock::MockPrompt;
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Child {
+  name: String,
+  age: u32,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Parent {
+  name: String,
+  children: Vec<Child>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Meters(f64);
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum Pet {
+  Cat { lives: u8 },
+  Dog(String),
+  Fish,
+  Pair(u8, bool),
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Owner {
+  nickname: Option<String>,
+  height: Option<Meters>,
+  pets: Vec<Pet>,
+  scores: BTreeMap<String, (u8, bool)>,
+}
+
+#[test]
+fn complete_document() {
+  let doc = json!({
+    "name": "Ann",
+    "children": [{"name": "Kid", "age": 4}, {"name": "Tot", "age": 2}],
+  });
+  let value: Parent = from_bare_prompt(
+    DocumentPrompt::new(&doc, MockPrompt::new(empty())).unwrap(),
+  )
+  .unwrap();
+  assert_eq!(
+    value,
+    Parent {
+      name: "Ann".into(),
+      children: vec![
+        Child {
+          name: "Kid".into(),
+          age: 4
+        },
+        Child {
+          name: "Tot".into(),
+          age: 2
+        },
+      ],
+    }
+  );
+}
+
+#[test]
+fn enums_options_and_maps() {
+  let doc = json!({
+    "nickname": null,
+    "height": 1.5,
+    "pets": [{"Cat": {"lives": 9}}, {"Dog": "Rex"}, "Fish", {"Pair": [1, true]}],
+    "scores": {"a": [1, false]},
+  });
+  let value: Owner = from_bare_prompt(
+    DocumentPrompt::new(&doc, MockPrompt::new(empty())).unwrap(),
+  )
+  .unwrap();
+  let mut scores = BTreeMap::new();
+  scores.insert("a".to_string(), (1, false));
+  assert_eq!(
+    value,
+    Owner {
+      nickname: None,
+      height: Some(Meters(1.5)),
+      pets: vec![
+        Pet::Cat { lives: 9 },
+        Pet::Dog("Rex".into()),
+        Pet::Fish,
+        Pet::Pair(1, true)
+      ],
+      scores,
+    }
+  );
+}
+
+#[test]
+fn missing_entries_fall_back() {
+  let doc = json!({
+    "children": [{"name": "Kid"}, {"name": "Tot", "age": "two"}],
+  });
+  let vec = vec!["Ann", "4", "2", "yes"].into_iter();
+  let mut mock = MockPrompt::new(vec).with_interactive();
+  let value: Parent = from_document(&doc, &mut mock).unwrap();
+  assert_eq!(value.name, "Ann");
+  assert_eq!(value.children[0].age, 4);
+  assert_eq!(value.children[1].age, 2);
+}
+
+#[test]
+fn revised_entries_fall_back() {
+  let doc = json!({"name": "Ann", "children": []});
+  let vec = vec!["no", "name", "Bea", "yes"].into_iter();
+  let value: Parent =
+    from_document(&doc, MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(value.name, "Bea");
+}
@@ -0,0 +1,94 @@
+use serde::Deserialize;
+use serde_spaniel::enumerate::Enumerator;
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum Mode {
+  Off,
+  On,
+  Auto(bool),
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Settings {
+  enabled: bool,
+  mode: Mode,
+  limit: Option<bool>,
+  pair: (bool, bool),
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Chain {
+  next: Option<Box<Chain>>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum List {
+  Cons(bool, Box<List>),
+  Nil,
+}
+
+#[test]
+fn small_types() {
+  let enumerator = Enumerator::new();
+  let modes: Vec<Mode> = enumerator.values().unwrap();
+  assert_eq!(
+    modes,
+    vec![Mode::Off, Mode::On, Mode::Auto(true), Mode::Auto(false)]
+  );
+  let settings: Vec<Settings> = enumerator.values().unwrap();
+  assert_eq!(settings.len(), 2 * 4 * 3 * 4);
+  assert_eq!(
+    settings[0],
+    Settings {
+      enabled: true,
+      mode: Mode::Off,
+      limit: None,
+      pair: (true, true),
+    }
+  );
+  let options: Vec<Option<bool>> = enumerator.values().unwrap();
+  assert_eq!(options, vec![None, Some(true), Some(false)]);
+}
+
+#[test]
+fn limits_and_samples() {
+  let mut enumerator = Enumerator::new();
+  let seqs: Vec<Vec<bool>> = enumerator.values().unwrap();
+  assert_eq!(seqs.len(), 1 + 2 + 4);
+  assert!(seqs[0].is_empty());
+  enumerator.set_max_length(1);
+  enumerator.set_samples("u16", vec!["7", "8"]);
+  let seqs: Vec<Vec<u16>> = enumerator.values().unwrap();
+  assert_eq!(seqs, vec![vec![], vec![7], vec![8]]);
+  enumerator.set_max_values(Some(2));
+  let seqs: Vec<Vec<u16>> = enumerator.values().unwrap();
+  assert_eq!(seqs.len(), 2);
+
+  enumerator.set_max_depth(7);
+  let chains: Vec<Chain> = enumerator.values().unwrap();
+  assert_eq!(chains.len(), 2);
+  enumerator.set_max_values(None);
+  let chains: Vec<Chain> = enumerator.values().unwrap();
+  assert_eq!(chains.len(), 3);
+}
+
+#[test]
+fn empty_samples() {
+  let mut enumerator = Enumerator::new();
+  enumerator.set_samples("u8", vec!["7"]);
+  enumerator.set_samples("u8", Vec::<String>::new());
+  let bytes: Vec<u8> = enumerator.values().unwrap();
+  assert_eq!(bytes, vec![0, 1, 255]);
+}
+
+#[test]
+fn recursive_enums_end() {
+  let mut enumerator = Enumerator::new();
+  enumerator.set_max_depth(1);
+  let lists: Vec<Vec<List>> = enumerator.values().unwrap();
+  assert_eq!(lists, vec![vec![]]);
+  enumerator.set_max_depth(4);
+  enumerator.set_max_length(1);
+  let lists: Vec<Vec<List>> = enumerator.values().unwrap();
+  assert_eq!(lists, vec![vec![], vec![List::Nil]]);
+}
@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use serde_spaniel::flat::EnvPrompt;
+use serde_spaniel::prompt::ReportKind;
+use serde_spaniel::*;
+use std::collections::BTreeMap;
+use std::iter::empty;
+
+use super::mock::{LogEntry, MockPrompt};
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Child {
+  name: String,
+  age: u32,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum Pet {
+  Cat { lives: u8 },
+  Fish,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Parent {
+  name: String,
+  children: Vec<Child>,
+  pet: Pet,
+}
+
+fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+  pairs
+    .iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect()
+}
+
+#[test]
+fn env_vars() {
+  let vars = vars(&[
+    ("APP__NAME", "Ann"),
+    ("APP__CHILDREN__0__NAME", "Kid"),
+    ("APP__CHILDREN__0__AGE", "4"),
+    ("APP__PET__CAT__LIVES", "9"),
+    ("APP__NMAE", "Bea"),
+    ("OTHER", "x"),
+  ]);
+  let mut mock = MockPrompt::new(empty());
+  let mut env = EnvPrompt::from_vars("APP", vars, &mut mock);
+  let value: Parent = from_bare_prompt(&mut env).unwrap();
+  env.report_unused().unwrap();
+  assert_eq!(
+    value,
+    Parent {
+      name: "Ann".into(),
+      children: vec![Child {
+        name: "Kid".into(),
+        age: 4
+      }],
+      pet: Pet::Cat { lives: 9 },
+    }
+  );
+  let log = mock.into_log();
+  assert!(log.contains(&LogEntry::Report(
+    ReportKind::Help,
+    "Unused environment variable: APP__NMAE".into()
+  )));
+  assert_eq!(
+    log
+      .iter()
+      .filter(|e| matches!(e, LogEntry::Report(_, _)))
+      .count(),
+    1
+  );
+}
+
+#[test]
+fn args_with_fallback() {
+  let args = vec![
+    "--name=Ann".to_string(),
+    "--children.0.name=Kid".to_string(),
+    "--pet=Fish".to_string(),
+    "bogus".to_string(),
+  ];
+  let vec = vec!["4", "yes"].into_iter();
+  let mut mock = MockPrompt::new(vec).with_interactive();
+  let value: Parent = from_args(args, &mut mock).unwrap();
+  assert_eq!(
+    value,
+    Parent {
+      name: "Ann".into(),
+      children: vec![Child {
+        name: "Kid".into(),
+        age: 4
+      }],
+      pet: Pet::Fish,
+    }
+  );
+  assert!(mock.into_log().contains(&LogEntry::Report(
+    ReportKind::Help,
+    "Unused argument: bogus".into()
+  )));
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Notes {
+  title: Option<String>,
+  lines: Vec<String>,
+  tags: Vec<String>,
+}
+
+#[test]
+fn flat_round_trip() {
+  let value = Parent {
+    name: "Ann".into(),
+    children: vec![
+      Child {
+        name: "Kid".into(),
+        age: 4,
+      },
+      Child {
+        name: "Tot".into(),
+        age: 1,
+      },
+    ],
+    pet: Pet::Cat { lives: 9 },
+  };
+  let s = to_flat_string(&value).unwrap();
+  assert_eq!(
+    s,
+    "name=Ann\n\
+     children.0.name=Kid\n\
+     children.0.age=4\n\
+     children.1.name=Tot\n\
+     children.1.age=1\n\
+     pet.Cat.lives=9\n"
+  );
+  assert_eq!(from_flat_str::<Parent>(&s).unwrap(), value);
+}
+
+#[test]
+fn flat_quoting() {
+  let value = Notes {
+    title: None,
+    lines: vec!["".into(), " a=b ".into(), "\"x\"\n\\".into()],
+    tags: vec![],
+  };
+  let s = to_flat_string(&value).unwrap();
+  assert_eq!(
+    s,
+    "title=\n\
+     lines.0=\"\"\n\
+     lines.1=\" a=b \"\n\
+     lines.2=\"\\\"x\\\"\\n\\\\\"\n\
+     tags=[]\n"
+  );
+  assert_eq!(from_flat_str::<Notes>(&s).unwrap(), value);
+  let edited = "# Comment\n\ntags.1=b\ntags.0=a\nlines=\ntitle=Hi\n";
+  assert_eq!(
+    from_flat_str::<Notes>(edited).unwrap(),
+    Notes {
+      title: Some("Hi".into()),
+      lines: vec![],
+      tags: vec!["a".into(), "b".into()],
+    }
+  );
+  assert!(matches!(
+    from_flat_str::<Notes>("title"),
+    Err(Error::FlatError(_))
+  ));
+}
+
+#[test]
+fn flat_numeric_keys() {
+  let mut value = BTreeMap::new();
+  value.insert(1u32, "a".to_string());
+  value.insert(5, "b".to_string());
+  let s = to_flat_string(&value).unwrap();
+  assert_eq!(s, "\"1\"=a\n\"5\"=b\n");
+  assert_eq!(from_flat_str::<BTreeMap<u32, String>>(&s).unwrap(), value);
+}
+
+#[test]
+fn flat_string_keys_kept() {
+  let mut value = BTreeMap::new();
+  value.insert("007".to_string(), 1u8);
+  value.insert("7".to_string(), 2);
+  let s = to_flat_string(&value).unwrap();
+  assert_eq!(from_flat_str::<BTreeMap<String, u8>>(&s).unwrap(), value);
+}
+
+type Empties = (
+  Option<Vec<u8>>,
+  Option<BTreeMap<String, u8>>,
+  Option<Vec<u8>>,
+);
+
+#[test]
+fn flat_empty_collections() {
+  let value: Empties = (Some(vec![]), Some(BTreeMap::new()), None);
+  let s = to_flat_string(&value).unwrap();
+  assert_eq!(s, "0=[]\n1={}\n2=\n");
+  assert_eq!(from_flat_str::<Empties>(&s).unwrap(), value);
+  let value = Notes {
+    title: Some("[]".into()),
+    lines: vec!["{}".into()],
+    tags: vec![],
+  };
+  let s = to_flat_string(&value).unwrap();
+  assert_eq!(from_flat_str::<Notes>(&s).unwrap(), value);
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use serde_spaniel::introspect::Shape;
+use serde_spaniel::*;
+use std::collections::BTreeMap;
+
+#[derive(Deserialize, Serialize)]
+struct Meters(f64);
+
+#[derive(Deserialize, Serialize)]
+enum Pet {
+  Cat { lives: u8 },
+  Fish,
+  Dog(String),
+}
+
+#[derive(Deserialize, Serialize)]
+struct Owner {
+  name: String,
+  height: Meters,
+  pets: Vec<Pet>,
+  nicknames: BTreeMap<String, Option<bool>>,
+  location: (i32, i32),
+}
+
+#[derive(Deserialize, Serialize)]
+struct Tree {
+  label: char,
+  children: Vec<Tree>,
+}
+
+#[derive(Deserialize, Serialize)]
+enum Expr {
+  Add(Box<Expr>, Box<Expr>),
+  Neg(Box<Expr>),
+  Num(i64),
+}
+
+#[derive(Deserialize, Serialize)]
+struct W<T>(T);
+
+#[derive(Deserialize, Serialize)]
+enum Never {}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct Pascal {
+  name: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "length")]
+struct Length(Meters);
+
+fn prim(label: &'static str) -> Shape {
+  Shape::Primitive(label)
+}
+
+#[test]
+fn describe_struct() {
+  let shape = describe::<Owner>().unwrap();
+  assert_eq!(
+    shape,
+    Shape::Struct(
+      "Owner".into(),
+      vec![
+        ("name".into(), prim("string")),
+        (
+          "height".into(),
+          Shape::Newtype("Meters".into(), Box::new(prim("f64")))
+        ),
+        (
+          "pets".into(),
+          Shape::Seq(Box::new(Shape::Enum(
+            "Pet".into(),
+            vec![
+              (
+                "Cat".into(),
+                Shape::Struct("Cat".into(), vec![("lives".into(), prim("u8"))])
+              ),
+              ("Fish".into(), Shape::Unit),
+              ("Dog".into(), prim("string")),
+            ]
+          )))
+        ),
+        (
+          "nicknames".into(),
+          Shape::Map(
+            Box::new(prim("string")),
+            Box::new(Shape::Option(Box::new(prim("bool"))))
+          )
+        ),
+        (
+          "location".into(),
+          Shape::Tuple("tuple".into(), vec![prim("i32"), prim("i32")])
+        ),
+      ]
+    )
+  );
+  assert!(!shape.is_recursive());
+}
+
+#[test]
+fn describe_recursive() {
+  let shape = describe::<Tree>().unwrap();
+  assert_eq!(
+    shape,
+    Shape::Struct(
+      "Tree".into(),
+      vec![
+        ("label".into(), prim("char")),
+        (
+          "children".into(),
+          Shape::Seq(Box::new(Shape::Recursive("Tree".into())))
+        ),
+      ]
+    )
+  );
+  assert!(shape.is_recursive());
+
+  let recursive = || Shape::Recursive("Expr".into());
+  assert_eq!(
+    describe::<Expr>().unwrap(),
+    Shape::Enum(
+      "Expr".into(),
+      vec![
+        (
+          "Add".into(),
+          Shape::Tuple("Add".into(), vec![recursive(), recursive()])
+        ),
+        ("Neg".into(), recursive()),
+        ("Num".into(), prim("i64")),
+      ]
+    )
+  );
+}
+
+#[test]
+fn describe_generic_nesting() {
+  let shape = describe::<W<W<u8>>>().unwrap();
+  assert_eq!(
+    shape,
+    Shape::Newtype(
+      "W".into(),
+      Box::new(Shape::Newtype("W".into(), Box::new(prim("u8"))))
+    )
+  );
+  assert!(!shape.is_recursive());
+}
+
+#[test]
+fn describe_empty_enum() {
+  assert_eq!(
+    describe::<Never>().unwrap(),
+    Shape::Enum("Never".into(), vec![])
+  );
+  assert_eq!(
+    describe::<Option<Never>>().unwrap(),
+    Shape::Option(Box::new(Shape::Enum("Never".into(), vec![])))
+  );
+}
+
+#[test]
+fn describe_renamed() {
+  let pascal =
+    || Shape::Struct("Pascal".into(), vec![("Name".into(), prim("string"))]);
+  assert_eq!(describe::<Pascal>().unwrap(), pascal());
+  assert_eq!(
+    describe::<W<Pascal>>().unwrap(),
+    Shape::Newtype("W".into(), Box::new(pascal()))
+  );
+  assert_eq!(
+    describe::<Length>().unwrap(),
+    Shape::Newtype(
+      "length".into(),
+      Box::new(Shape::Newtype("Meters".into(), Box::new(prim("f64"))))
+    )
+  );
+}
@@ -0,0 +1,135 @@
+use serde::Serialize;
+use serde_spaniel::markup::{HtmlWriter, MarkdownWriter};
+use serde_spaniel::*;
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+struct Item {
+  name: String,
+  qty: u32,
+}
+
+#[derive(Serialize)]
+enum Status {
+  Shipped { carrier: String },
+  Pending,
+}
+
+#[derive(Serialize)]
+struct Order {
+  id: u32,
+  note: Option<String>,
+  items: Vec<Item>,
+  tags: Vec<String>,
+  status: Status,
+  extra: BTreeMap<String, String>,
+}
+
+fn order() -> Order {
+  let mut extra = BTreeMap::new();
+  extra.insert("gift".to_string(), "yes".to_string());
+  Order {
+    id: 7,
+    note: None,
+    items: vec![
+      Item {
+        name: "Pen | blue".into(),
+        qty: 2,
+      },
+      Item {
+        name: "<Ink>".into(),
+        qty: 1,
+      },
+    ],
+    tags: vec!["# rush".into()],
+    status: Status::Shipped {
+      carrier: "Post*".into(),
+    },
+    extra,
+  }
+}
+
+const MARKDOWN: &str = "# Order
+
+- **id**: 7
+- **note**: (none)
+
+## items
+
+| name | qty |
+| --- | --- |
+| Pen \\| blue | 2 |
+| \\<Ink\\> | 1 |
+
+## tags
+
+- \\# rush
+
+## status
+
+### Shipped
+
+- **carrier**: Post\\*
+
+## extra
+
+- **gift**: yes
+";
+
+#[test]
+fn markdown_bare() {
+  let mut writer = MarkdownWriter::new();
+  to_bare_prompt(&order(), &mut writer).unwrap();
+  assert_eq!(writer.into_string(), MARKDOWN);
+}
+
+#[test]
+fn markdown_compacted() {
+  let mut writer = MarkdownWriter::new();
+  to_prompt(&order(), &mut writer).unwrap();
+  assert_eq!(writer.into_string(), MARKDOWN);
+}
+
+#[test]
+fn html_page() {
+  let mut order = order();
+  order.status = Status::Pending;
+  order.items.truncate(1);
+  order.tags.clear();
+  let mut writer = HtmlWriter::new();
+  to_prompt(&order, &mut writer).unwrap();
+  assert_eq!(
+    writer.into_string(),
+    "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Order</title>
+</head>
+<body>
+<h1>Order</h1>
+<dl>
+<dt>id</dt>
+<dd>7</dd>
+<dt>note</dt>
+<dd>(none)</dd>
+<dt>status</dt>
+<dd>Pending</dd>
+</dl>
+<h2>items</h2>
+<table>
+<tr><th>name</th><th>qty</th></tr>
+<tr><td>Pen | blue</td><td>2</td></tr>
+</table>
+<h2>tags</h2>
+<p>(empty)</p>
+<h2>extra</h2>
+<dl>
+<dt>gift</dt>
+<dd>yes</dd>
+</dl>
+</body>
+</html>
+"
+  );
+}
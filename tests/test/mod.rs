@@ -1,5 +1,18 @@
 pub mod de;
+pub mod diff;
+pub mod document;
+pub mod enumerate;
+pub mod flat;
 pub mod golden;
+pub mod introspect;
+pub mod markup;
 pub mod mock;
 pub mod prompt;
+pub mod random;
+#[cfg(feature = "json_schema")]
+pub mod schema;
 pub mod ser;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transcript;
+pub mod util;
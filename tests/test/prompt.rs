@@ -1,5 +1,7 @@
 use serde::Deserialize;
-use serde_spaniel::prompt::{CompactPrompt, RequestKind};
+use serde_spaniel::prompt::{
+  CompactPrompt, PagerPrompt, ReplayPrompt, ReportKind, RequestKind,
+};
 use serde_spaniel::*;
 
 use super::mock::{LogEntry, MockPrompt};
@@ -71,3 +73,109 @@ fn compact_scope() {
     ]
   );
 }
+
+#[test]
+fn replay_mismatch() {
+  let vec = vec!["Bob", "40"].into_iter();
+  let mut replay = ReplayPrompt::new(MockPrompt::new(vec));
+  replay.record();
+  let _: (String, u32) = from_bare_prompt(&mut replay).unwrap();
+  replay.replay().unwrap();
+  let value: Result<(String, String)> = from_bare_prompt(&mut replay);
+  assert_eq!(value, Err(Error::ReplayMismatch));
+}
+
+#[test]
+fn replay_mismatch_interactive() {
+  let vec = vec!["Bob", "40", "forty"].into_iter();
+  let mut replay = ReplayPrompt::new(MockPrompt::new(vec).with_interactive());
+  replay.record();
+  let _: (String, u32) = from_bare_prompt(&mut replay).unwrap();
+  replay.replay().unwrap();
+  let value: (String, String) = from_bare_prompt(&mut replay).unwrap();
+  assert_eq!(value, ("Bob".to_string(), "forty".to_string()));
+}
+
+#[test]
+fn pager_collapses_seqs() {
+  let value: Vec<u8> = vec![1, 2, 3, 4, 5];
+  let mut mock = MockPrompt::new(vec![].into_iter());
+  let mut pager = PagerPrompt::new(&mut mock);
+  pager.set_max_elements(Some(2));
+  to_prompt(&value, &mut pager).unwrap();
+  assert_eq!(
+    mock.into_log(),
+    vec![
+      LogEntry::BeginScope("seq".into(), Some(6)),
+      LogEntry::BeginScope("[0]".into(), None),
+      LogEntry::Response(
+        RequestKind::Question,
+        "Add element?".into(),
+        &[],
+        "yes".into()
+      ),
+      LogEntry::Response(RequestKind::Datum, "u8".into(), &[], "1".into()),
+      LogEntry::EndScope,
+      LogEntry::BeginScope("[1]".into(), None),
+      LogEntry::Response(
+        RequestKind::Question,
+        "Add element?".into(),
+        &[],
+        "yes".into()
+      ),
+      LogEntry::Response(RequestKind::Datum, "u8".into(), &[], "2".into()),
+      LogEntry::EndScope,
+      LogEntry::Report(ReportKind::Help, "... 3 more".into()),
+      LogEntry::EndScope,
+    ]
+  );
+}
+
+#[test]
+fn pager_collapses_maps() {
+  let value: std::collections::BTreeMap<u8, bool> =
+    vec![(1, true), (2, false), (3, true)].into_iter().collect();
+  let mut mock = MockPrompt::new(vec![].into_iter());
+  let mut pager = PagerPrompt::new(&mut mock);
+  pager.set_max_elements(Some(1));
+  to_prompt(&value, &mut pager).unwrap();
+  let log = mock.into_log();
+  assert_eq!(log.len(), 8);
+  assert_eq!(
+    log[6],
+    LogEntry::Report(ReportKind::Help, "... 2 more".into())
+  );
+}
+
+#[test]
+fn pager_pauses_and_quits() {
+  let value: Vec<u8> = vec![1, 2, 3];
+  let vec = vec!["", "q"].into_iter();
+  let mut mock = MockPrompt::new(vec).with_interactive();
+  let mut pager = PagerPrompt::new(&mut mock);
+  pager.set_page_size(Some(3));
+  to_prompt(&value, &mut pager).unwrap();
+  let more = |s: &str| {
+    LogEntry::Response(RequestKind::Question, "--More--".into(), &[], s.into())
+  };
+  assert_eq!(
+    mock.into_log(),
+    vec![
+      LogEntry::BeginScope("seq".into(), Some(4)),
+      LogEntry::BeginScope("[0]".into(), None),
+      LogEntry::Response(
+        RequestKind::Question,
+        "Add element?".into(),
+        &[],
+        "yes".into()
+      ),
+      more(""),
+      LogEntry::Response(RequestKind::Datum, "u8".into(), &[], "1".into()),
+      LogEntry::EndScope,
+      LogEntry::BeginScope("[1]".into(), None),
+      more("q"),
+      LogEntry::EndScope,
+      LogEntry::EndScope,
+    ]
+  );
+}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use serde_spaniel::random::{BytesPrompt, LengthDistribution, RandomPrompt};
+use serde_spaniel::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum Colour {
+  Red,
+  Green,
+  Rgb(u8, u8, u8),
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Palette {
+  name: String,
+  default: Option<Colour>,
+  colours: Vec<Colour>,
+  weights: BTreeMap<String, f64>,
+  #[serde(with = "serde_bytes")]
+  data: Vec<u8>,
+  flag: bool,
+  offset: i64,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Tree {
+  label: char,
+  children: Vec<Tree>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum List {
+  Cons(u8, Box<List>),
+  Nil,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum Endless {
+  More(Box<Endless>),
+}
+
+fn depth(tree: &Tree) -> usize {
+  1 + tree.children.iter().map(depth).max().unwrap_or(0)
+}
+
+#[test]
+fn seeded_values() {
+  for seed in 0..20 {
+    let a: Palette = from_bare_prompt(RandomPrompt::seeded(seed)).unwrap();
+    let b: Palette = from_bare_prompt(RandomPrompt::seeded(seed)).unwrap();
+    assert_eq!(a, b);
+    assert!(a.colours.len() <= 4);
+    assert!(a.data.len() <= 4);
+    assert!(a.name.chars().all(|c| c.is_ascii_alphanumeric()));
+  }
+  let values: Vec<Palette> = (0..20)
+    .map(|seed| from_bare_prompt(RandomPrompt::seeded(seed)).unwrap())
+    .collect();
+  assert!(values.iter().any(|v| v.default.is_none()));
+  assert!(values.iter().any(|v| v.default.is_some()));
+  assert!(values.iter().any(|v| v.flag));
+  assert!(values.iter().any(|v| !v.flag));
+}
+
+#[test]
+fn lengths_and_depth() {
+  let mut prompt = RandomPrompt::seeded(1);
+  prompt.set_lengths(LengthDistribution::Uniform(3, 3));
+  let value: Palette = from_bare_prompt(&mut prompt).unwrap();
+  assert_eq!(value.colours.len(), 3);
+  assert_eq!(value.data.len(), 3);
+  assert_eq!(value.name.len(), 3);
+
+  let mut prompt = RandomPrompt::seeded(2);
+  prompt.set_lengths(LengthDistribution::Geometric(0.9));
+  prompt.set_max_depth(12);
+  let tree: Tree = from_bare_prompt(&mut prompt).unwrap();
+  assert!(depth(&tree) > 1);
+  assert!(depth(&tree) <= 4);
+}
+
+#[test]
+fn recursive_variants_end() {
+  let mut prompt = RandomPrompt::seeded(4);
+  prompt.set_max_depth(0);
+  let value: List = from_bare_prompt(&mut prompt).unwrap();
+  assert!(matches!(value, List::Cons(_, ref tail) if **tail == List::Nil));
+  let res: Result<Endless> = from_bare_prompt(&mut prompt);
+  assert_eq!(res, Err(Error::OutOfResponses));
+}
+
+#[test]
+fn capped_lengths() {
+  let mut prompt = RandomPrompt::seeded(3);
+  prompt.set_lengths(LengthDistribution::Geometric(1.0));
+  let value: Vec<u8> = from_bare_prompt(&mut prompt).unwrap();
+  assert_eq!(value.len(), 1024);
+
+  prompt.set_lengths(LengthDistribution::Uniform(0, usize::MAX));
+  prompt.set_max_length(10);
+  let value: String = from_bare_prompt(&mut prompt).unwrap();
+  assert!(value.len() <= 10);
+}
+
+#[test]
+fn bytes_values() {
+  let value: Palette = from_bare_prompt(BytesPrompt::new(&[])).unwrap();
+  assert_eq!(
+    value,
+    Palette {
+      name: "".into(),
+      default: None,
+      colours: vec![],
+      weights: BTreeMap::new(),
+      data: vec![],
+      flag: true,
+      offset: 0,
+    }
+  );
+
+  let data = [
+    2, b'H', b'i', // name
+    1, 2, 7, 8, 9, // default: Some(Rgb(7, 8, 9))
+    1, 1, 0, // colours: [Green]
+    0, // weights: {}
+    1, 0xff, 0, // data: [255]
+    1, // flag: false
+    0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // offset: -2
+  ];
+  let mut prompt = BytesPrompt::new(&data);
+  let value: Palette = from_bare_prompt(&mut prompt).unwrap();
+  assert_eq!(
+    value,
+    Palette {
+      name: "Hi".into(),
+      default: Some(Colour::Rgb(7, 8, 9)),
+      colours: vec![Colour::Green],
+      weights: BTreeMap::new(),
+      data: vec![255],
+      flag: false,
+      offset: -2,
+    }
+  );
+  assert_eq!(prompt.remaining(), 0);
+
+  let mut state = 1u32;
+  for len in 0..200 {
+    let data: Vec<u8> = (0..len)
+      .map(|_| {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        (state >> 16) as u8
+      })
+      .collect();
+    let _: Palette = from_bare_prompt(BytesPrompt::new(&data)).unwrap();
+    let _: Tree = from_bare_prompt(BytesPrompt::new(&data)).unwrap();
+  }
+}
+
+#[test]
+fn bytes_recursive_variants_end() {
+  let value: List = from_bare_prompt(BytesPrompt::new(&[])).unwrap();
+  assert_eq!(value, List::Cons(0, Box::new(List::Nil)));
+  let value: Vec<List> = from_bare_prompt(BytesPrompt::new(&[1, 1])).unwrap();
+  assert_eq!(value, vec![List::Nil]);
+  let res: Result<Endless> = from_bare_prompt(BytesPrompt::new(&[]));
+  assert_eq!(res, Err(Error::OutOfResponses));
+}
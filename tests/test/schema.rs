@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_spaniel::schema::json_schema;
+use std::collections::BTreeMap;
+
+#[derive(Deserialize, Serialize)]
+enum Pet {
+  Cat { lives: u8 },
+  Fish,
+  Dog(String),
+}
+
+#[derive(Deserialize, Serialize)]
+struct Owner {
+  name: String,
+  age: Option<i8>,
+  pets: Vec<Pet>,
+  location: (f64, f64),
+}
+
+#[derive(Deserialize, Serialize)]
+struct Tree {
+  label: char,
+  children: Vec<Tree>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct W<T>(T);
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct Pascal {
+  name: String,
+}
+
+mod first {
+  use serde::{Deserialize, Serialize};
+
+  #[derive(Deserialize, Serialize)]
+  pub struct Config {
+    pub next: Option<Box<Config>>,
+  }
+}
+
+mod second {
+  use serde::{Deserialize, Serialize};
+
+  #[derive(Deserialize, Serialize)]
+  pub struct Config {
+    pub children: Vec<Config>,
+  }
+}
+
+#[derive(Deserialize, Serialize)]
+struct Configs {
+  first: first::Config,
+  second: second::Config,
+  again: first::Config,
+}
+
+#[derive(Deserialize, Ord, PartialEq, PartialOrd, Eq, Serialize)]
+enum Colour {
+  Red,
+  Green,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Keys {
+  ids: BTreeMap<i32, bool>,
+  colours: BTreeMap<Colour, u8>,
+  names: BTreeMap<String, u8>,
+}
+
+#[test]
+fn owner_schema() {
+  assert_eq!(
+    json_schema::<Owner>().unwrap(),
+    json!({
+      "$schema": "https://json-schema.org/draft/2020-12/schema",
+      "title": "Owner",
+      "type": "object",
+      "properties": {
+        "name": { "type": "string" },
+        "age": {
+          "anyOf": [
+            { "type": "integer", "minimum": -128, "maximum": 127 },
+            { "type": "null" },
+          ]
+        },
+        "pets": {
+          "type": "array",
+          "items": {
+            "title": "Pet",
+            "oneOf": [
+              {
+                "type": "object",
+                "properties": {
+                  "Cat": {
+                    "title": "Cat",
+                    "type": "object",
+                    "properties": {
+                      "lives": { "type": "integer", "minimum": 0, "maximum": 255 }
+                    },
+                    "required": ["lives"],
+                  }
+                },
+                "required": ["Cat"],
+                "additionalProperties": false,
+              },
+              { "const": "Fish" },
+              {
+                "type": "object",
+                "properties": { "Dog": { "type": "string" } },
+                "required": ["Dog"],
+                "additionalProperties": false,
+              },
+            ]
+          }
+        },
+        "location": {
+          "type": "array",
+          "prefixItems": [{ "type": "number" }, { "type": "number" }],
+          "items": false,
+          "minItems": 2,
+        },
+      },
+      "required": ["name", "pets", "location"],
+    })
+  );
+}
+
+#[test]
+fn recursive_schema() {
+  assert_eq!(
+    json_schema::<Tree>().unwrap(),
+    json!({
+      "$schema": "https://json-schema.org/draft/2020-12/schema",
+      "$ref": "#/$defs/Tree",
+      "$defs": {
+        "Tree": {
+          "title": "Tree",
+          "type": "object",
+          "properties": {
+            "label": { "type": "string", "minLength": 1, "maxLength": 1 },
+            "children": {
+              "type": "array",
+              "items": { "$ref": "#/$defs/Tree" },
+            },
+          },
+          "required": ["label", "children"],
+        }
+      },
+    })
+  );
+}
+
+#[test]
+fn generic_nesting_schema() {
+  assert_eq!(
+    json_schema::<W<W<u8>>>().unwrap(),
+    json!({
+      "$schema": "https://json-schema.org/draft/2020-12/schema",
+      "type": "integer",
+      "minimum": 0,
+      "maximum": 255,
+    })
+  );
+}
+
+#[test]
+fn renamed_field_schema() {
+  assert_eq!(
+    json_schema::<W<Pascal>>().unwrap(),
+    json!({
+      "$schema": "https://json-schema.org/draft/2020-12/schema",
+      "title": "Pascal",
+      "type": "object",
+      "properties": { "Name": { "type": "string" } },
+      "required": ["Name"],
+    })
+  );
+}
+
+#[test]
+fn same_named_schemas() {
+  let config = |field: &str, inner: serde_json::Value| {
+    json!({
+      "title": "Config",
+      "type": "object",
+      "properties": { field: inner },
+      "required": if field == "next" { json!([]) } else { json!([field]) },
+    })
+  };
+  assert_eq!(
+    json_schema::<Configs>().unwrap(),
+    json!({
+      "$schema": "https://json-schema.org/draft/2020-12/schema",
+      "title": "Configs",
+      "type": "object",
+      "properties": {
+        "first": { "$ref": "#/$defs/Config" },
+        "second": { "$ref": "#/$defs/Config_2" },
+        "again": { "$ref": "#/$defs/Config" },
+      },
+      "required": ["first", "second", "again"],
+      "$defs": {
+        "Config": config(
+          "next",
+          json!({
+            "anyOf": [{ "$ref": "#/$defs/Config" }, { "type": "null" }]
+          })
+        ),
+        "Config_2": config(
+          "children",
+          json!({ "type": "array", "items": { "$ref": "#/$defs/Config_2" } })
+        ),
+      },
+    })
+  );
+}
+
+#[test]
+fn map_key_schemas() {
+  let byte = json!({ "type": "integer", "minimum": 0, "maximum": 255 });
+  assert_eq!(
+    json_schema::<Keys>().unwrap(),
+    json!({
+      "$schema": "https://json-schema.org/draft/2020-12/schema",
+      "title": "Keys",
+      "type": "object",
+      "properties": {
+        "ids": {
+          "type": "object",
+          "additionalProperties": { "type": "boolean" },
+          "propertyNames": { "pattern": "^-?[0-9]+$" },
+        },
+        "colours": {
+          "type": "object",
+          "additionalProperties": byte,
+          "propertyNames": { "enum": ["Red", "Green"] },
+        },
+        "names": { "type": "object", "additionalProperties": byte },
+      },
+      "required": ["ids", "colours", "names"],
+    })
+  );
+}
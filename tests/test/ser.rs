@@ -1,9 +1,10 @@
 use serde::Serialize;
+use serde_spaniel::prompt::RequestKind;
 use serde_spaniel::*;
 use std::iter::empty;
 
 use super::golden::{self, Golden};
-use super::mock::MockPrompt;
+use super::mock::{LogEntry, MockPrompt};
 
 fn test_ser<G: Golden>()
 where
@@ -49,3 +50,29 @@ fn map_of_enums_and_newtypes() {
 fn bytes() {
   test_ser::<golden::BytesCase>()
 }
+
+#[derive(Serialize)]
+enum Wrapper {
+  Byte(u8),
+}
+
+#[test]
+fn newtype_variant_within_enum_scope() {
+  let mut prompt = MockPrompt::new(empty());
+  to_bare_prompt(&Wrapper::Byte(5), &mut prompt).unwrap();
+  // The scope is of size one, so that it's compacted, and ends with the value
+  assert_eq!(
+    prompt.into_log(),
+    vec![
+      LogEntry::BeginScope("Wrapper".into(), Some(1)),
+      LogEntry::Response(
+        RequestKind::Datum,
+        "variant".into(),
+        &[],
+        "Byte".into()
+      ),
+      LogEntry::Response(RequestKind::Datum, "u8".into(), &[], "5".into()),
+      LogEntry::EndScope,
+    ]
+  );
+}
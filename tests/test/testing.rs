@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use serde_spaniel::prompt::ReportKind;
+use serde_spaniel::testing::{
+  assert_dialogue, assert_round_trip, LogEntry, ScriptedPrompt,
+};
+use serde_spaniel::*;
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Point {
+  x: u32,
+  y: Option<u32>,
+}
+
+#[test]
+fn round_trip() {
+  assert_round_trip(&Point { x: 1, y: Some(2) }, &["1", "yes", "2"]);
+  assert_round_trip(&vec!["a".to_string()], &["yes", "a", "no"]);
+}
+
+#[test]
+#[should_panic(expected = "serialised responses")]
+fn round_trip_mismatch() {
+  assert_round_trip(&Point { x: 1, y: None }, &["1", "yes", "2"]);
+}
+
+#[test]
+fn out_of_responses() {
+  let mut prompt = ScriptedPrompt::new(vec!["1"]);
+  let res: Result<Point> = from_bare_prompt(&mut prompt);
+  assert!(matches!(res, Err(Error::OutOfResponses)));
+  assert_eq!(prompt.responses(), ["1"]);
+  assert_eq!(prompt.scope_names(), ["Point", "x", "y", "option"]);
+}
+
+#[test]
+fn undo_restart_and_bad_responses() {
+  let log = assert_dialogue(
+    &[
+      "x", "1", "!undo", "3", "no", "!restart", "4", "yes", "5", "yes",
+    ],
+    &Point { x: 4, y: Some(5) },
+  );
+  assert!(log.contains(&LogEntry::Report(
+    ReportKind::BadResponse,
+    "Failed to parse: invalid digit found in string".into()
+  )));
+}
+
+#[test]
+fn labelled() {
+  let mut prompt =
+    ScriptedPrompt::labelled(vec![("u32", "1"), ("Some value?", "no")]);
+  let res: Point = from_bare_prompt(&mut prompt).unwrap();
+  assert_eq!(res, Point { x: 1, y: None });
+}
+
+#[test]
+#[should_panic(expected = "prompt of request")]
+fn labelled_mismatch() {
+  let mut prompt = ScriptedPrompt::labelled(vec![("u32", "1"), ("u32", "2")]);
+  let _: Result<Point> = from_bare_prompt(&mut prompt);
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use serde_spaniel::stdio::ReadWritePrompt;
+use serde_spaniel::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum Pet {
+  Cat { lives: u8 },
+  Dog(String),
+  Fish,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Owner {
+  name: String,
+  nickname: Option<String>,
+  pets: Vec<Pet>,
+  scores: BTreeMap<String, (u8, bool)>,
+}
+
+fn owner() -> Owner {
+  let mut scores = BTreeMap::new();
+  scores.insert("a: b".to_string(), (1, true));
+  Owner {
+    name: "!Ann\nLee \\o/".into(),
+    nickname: None,
+    pets: vec![Pet::Cat { lives: 9 }, Pet::Dog("Rex {".into()), Pet::Fish],
+    scores,
+  }
+}
+
+#[test]
+fn round_trip() {
+  let value = owner();
+  let text = to_transcript_string(&value).unwrap();
+  let read: Owner = from_transcript_str(&text).unwrap();
+  assert_eq!(read, value);
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename = "Note: draft")]
+struct Draft {
+  #[serde(rename = "a: b")]
+  text: String,
+  n: u8,
+}
+
+#[test]
+fn names_with_colons() {
+  let value = Draft {
+    text: "open {".into(),
+    n: 1,
+  };
+  let text = to_transcript_string(&value).unwrap();
+  assert_eq!(
+    text,
+    "Note: draft {\n  a: b -> string: open \\{\n  n -> u8: 1\n}\n"
+  );
+  let read: Draft = from_transcript_str(&text).unwrap();
+  assert_eq!(read, value);
+}
+
+#[test]
+fn matches_read_write_prompt() {
+  let value = Owner {
+    name: "Ann".into(),
+    nickname: Some("Annie".into()),
+    pets: vec![Pet::Fish],
+    scores: BTreeMap::new(),
+  };
+  let mut buf = Vec::new();
+  to_prompt(&value, ReadWritePrompt::new_responder(&mut buf)).unwrap();
+  let text = to_transcript_string(&value).unwrap();
+  assert_eq!(String::from_utf8(buf).unwrap(), text);
+}
+
+#[test]
+fn compacted_newtype_variant() {
+  let value = vec![Pet::Dog("Rex".into())];
+  let text = to_transcript_string(&value).unwrap();
+  assert_eq!(
+    text,
+    concat!(
+      "seq {\n  [0] {\n    Add element?: yes\n",
+      "    Pet -> variant: Dog\n    Pet -> string: Rex\n",
+      "  }\n  [1] {\n    Add element?: no\n  }\n}\n",
+    )
+  );
+  let read: Vec<Pet> = from_transcript_str(&text).unwrap();
+  assert_eq!(read, value);
+}
+
+#[test]
+fn hand_edited() {
+  let text = "Owner {
+name -> string: Bob
+nickname -> option {
+Some value?: yes
+string:
+}
+
+pets -> seq {
+[0] {
+Add element?: no
+}
+}
+scores -> map {
+[0] {
+Add entry?: no
+}
+}
+}
+";
+  let read: Owner = from_transcript_str(text).unwrap();
+  assert_eq!(
+    read,
+    Owner {
+      name: "Bob".into(),
+      nickname: Some("".into()),
+      pets: vec![],
+      scores: BTreeMap::new(),
+    }
+  );
+}
+
+#[test]
+fn wrong_prompt() {
+  let text = "Owner {\n  age -> u32: 40\n}\n";
+  let res: Result<Owner> = from_transcript_str(text);
+  assert_eq!(
+    res,
+    Err(Error::TranscriptError(
+      "line 2: expected 'name -> string: ...', found 'age -> u32: ...'".into()
+    ))
+  );
+}
+
+#[test]
+fn bad_response() {
+  let text = "(u8, bool) {\n}\n";
+  let res: Result<(u8, bool)> = from_transcript_str(text);
+  assert!(matches!(res, Err(Error::TranscriptError(_))));
+  let res: Result<u8> = from_transcript_str("u8: 256\n");
+  assert!(
+    matches!(res, Err(Error::TranscriptError(msg)) if msg.starts_with("line 1: "))
+  );
+}
+
+#[test]
+fn trailing_content() {
+  let res: Result<u8> = from_transcript_str("u8: 1\nu8: 2\n");
+  assert_eq!(
+    res,
+    Err(Error::TranscriptError(
+      "line 2: unexpected 'u8: ...'".into()
+    ))
+  );
+}
@@ -0,0 +1,615 @@
+use serde::{Deserialize, Serialize};
+use serde_spaniel::prompt::ReportKind;
+use serde_spaniel::*;
+use std::convert::TryFrom;
+
+use super::mock::{LogEntry, MockPrompt};
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Person {
+  name: String,
+  age: u32,
+  tags: Vec<String>,
+}
+
+#[test]
+fn revise_by_number() {
+  let vec =
+    vec!["Bob", "40", "yes", "red", "no", "no", "2", "41", "yes"].into_iter();
+  let value: Person =
+    from_prompt_review(MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(
+    value,
+    Person {
+      name: "Bob".into(),
+      age: 41,
+      tags: vec!["red".into()],
+    }
+  );
+}
+
+#[test]
+fn revise_by_path() {
+  let vec = vec![
+    "Bob",
+    "40",
+    "yes",
+    "red",
+    "yes",
+    "green",
+    "no",
+    "no",
+    "tags.seq[0]",
+    "blue",
+    "yes",
+  ]
+  .into_iter();
+  let value: Person =
+    from_prompt_review(MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(
+    value,
+    Person {
+      name: "Bob".into(),
+      age: 40,
+      tags: vec!["blue".into(), "green".into()],
+    }
+  );
+}
+
+#[test]
+fn revise_whole_seq() {
+  let vec = vec!["Bob", "40", "yes", "red", "no", "no", "tags", "no", "yes"]
+    .into_iter();
+  let value: Person =
+    from_prompt_review(MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(
+    value,
+    Person {
+      name: "Bob".into(),
+      age: 40,
+      tags: vec![],
+    }
+  );
+}
+
+#[test]
+fn revise_unknown_field() {
+  let vec =
+    vec!["Bob", "40", "no", "no", "height", "age", "39", "yes"].into_iter();
+  let value: Person =
+    from_prompt_review(MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(
+    value,
+    Person {
+      name: "Bob".into(),
+      age: 39,
+      tags: vec![],
+    }
+  );
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Child {
+  name: String,
+  age: u32,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Parent {
+  name: String,
+  children: Vec<Child>,
+}
+
+#[test]
+fn up_to_scope_start() {
+  let vec = vec!["Ann", "yes", "Kid", "!up", "!up", "no", "yes"].into_iter();
+  let value: Parent =
+    from_prompt(MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(
+    value,
+    Parent {
+      name: "Ann".into(),
+      children: vec![],
+    }
+  );
+}
+
+#[test]
+fn back_to_path() {
+  let vec = vec![
+    "Ann",
+    "yes",
+    "Kid",
+    "3",
+    "yes",
+    "Tot",
+    "!back seq[0].Child.age",
+    "4",
+    "no",
+    "yes",
+  ]
+  .into_iter();
+  let value: Parent =
+    from_prompt(MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(
+    value,
+    Parent {
+      name: "Ann".into(),
+      children: vec![Child {
+        name: "Kid".into(),
+        age: 4,
+      }],
+    }
+  );
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Inner {
+  b: u32,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Outer {
+  x: Inner,
+  b: u32,
+}
+
+#[test]
+fn back_to_contiguous_path() {
+  let vec = vec!["1", "2", "!back Outer.b", "3", "yes"].into_iter();
+  let value: Outer =
+    from_prompt(MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(
+    value,
+    Outer {
+      x: Inner { b: 1 },
+      b: 3,
+    }
+  );
+  let path = |s: &str| s.parse::<path::ScopePath>().unwrap();
+  assert_eq!(path("Outer.x.Inner.b").find(&path("Outer.b")), None);
+  assert_eq!(path("Outer.x.Inner.b").find(&path("x.Inner")), Some(3));
+}
+
+#[test]
+fn back_to_unknown_path() {
+  let vec = vec!["Ann", "!back pets", "no", "yes"].into_iter();
+  let value: Parent =
+    from_prompt(MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(
+    value,
+    Parent {
+      name: "Ann".into(),
+      children: vec![],
+    }
+  );
+}
+
+#[test]
+fn redo_after_undo() {
+  let vec =
+    vec!["Bob", "40", "!undo2", "Bob", "!redo", "no", "yes"].into_iter();
+  let value: Person =
+    from_prompt(MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(
+    value,
+    Person {
+      name: "Bob".into(),
+      age: 40,
+      tags: vec![],
+    }
+  );
+}
+
+#[test]
+fn redo_after_divergence() {
+  let vec =
+    vec!["Bob", "40", "!undo2", "Bill", "!redo", "50", "no", "yes"].into_iter();
+  let value: Person =
+    from_prompt(MockPrompt::new(vec).with_interactive()).unwrap();
+  assert_eq!(
+    value,
+    Person {
+      name: "Bill".into(),
+      age: 50,
+      tags: vec![],
+    }
+  );
+}
+
+fn help_reports(log: Vec<LogEntry>) -> Vec<String> {
+  let mut reports = Vec::new();
+  for entry in log {
+    if let LogEntry::Report(ReportKind::Help, msg) = entry {
+      reports.push(msg);
+    }
+  }
+  reports
+}
+
+#[test]
+fn show_responses() {
+  let vec =
+    vec!["Ann", "yes", "Kid", "!show", "!show .", "3", "no", "yes"].into_iter();
+  let mut mock = MockPrompt::new(vec).with_interactive();
+  let _: Parent = from_prompt(&mut mock).unwrap();
+  let reports = help_reports(mock.into_log());
+  assert_eq!(
+    reports[..7],
+    [
+      "> Parent",
+      "    name: Ann",
+      ">   children.seq[0]",
+      "      Add element?: yes",
+      "      Child.name: Kid",
+      "> Parent.children.seq[0].Child",
+      "    name: Kid",
+    ]
+  );
+}
+
+#[test]
+fn history_and_restart() {
+  let vec = vec!["Bob", "40", "!history", "!r1", "41", "no", "yes"].into_iter();
+  let mut mock = MockPrompt::new(vec).with_interactive();
+  let value: Person = from_prompt(&mut mock).unwrap();
+  assert_eq!(value.age, 41);
+  let reports = help_reports(mock.into_log());
+  assert_eq!(
+    reports[..2],
+    [
+      "   0 Person.name (string): Bob",
+      "   1 Person.age (u32): 40"
+    ]
+  );
+}
+
+#[test]
+fn resume_after_cancel() {
+  let path = std::env::temp_dir().join("spaniel_resume_after_cancel");
+  let vec = vec!["Bob", "!cancel"].into_iter();
+  let res: Result<Person> =
+    from_prompt_resume(MockPrompt::new(vec).with_interactive(), &path);
+  assert_eq!(res, Err(Error::UserAction(UserAction::Cancel)));
+  let vec = vec!["40", "no", "yes"].into_iter();
+  let value: Person =
+    from_prompt_resume(MockPrompt::new(vec).with_interactive(), &path).unwrap();
+  assert_eq!(
+    value,
+    Person {
+      name: "Bob".into(),
+      age: 40,
+      tags: vec![],
+    }
+  );
+  assert!(!path.exists());
+}
+
+#[test]
+fn resume_different_type() {
+  let path = std::env::temp_dir().join("spaniel_resume_different_type");
+  let vec = vec!["Bob", "!cancel"].into_iter();
+  let res: Result<Person> =
+    from_prompt_resume(MockPrompt::new(vec).with_interactive(), &path);
+  assert_eq!(res, Err(Error::UserAction(UserAction::Cancel)));
+  let res: Result<Parent> =
+    from_prompt_resume(MockPrompt::new(vec![].into_iter()), &path);
+  assert_eq!(res, Err(Error::SessionMismatch));
+  std::fs::remove_file(&path).unwrap();
+}
+
+// Shaped like Person, except within the seq
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename = "Person")]
+struct NumberedPerson {
+  name: String,
+  age: u32,
+  tags: Vec<u32>,
+}
+
+#[test]
+fn resume_different_element_type() {
+  let path = std::env::temp_dir().join("spaniel_resume_different_element");
+  let vec = vec!["Bob", "!cancel"].into_iter();
+  let res: Result<Person> =
+    from_prompt_resume(MockPrompt::new(vec).with_interactive(), &path);
+  assert_eq!(res, Err(Error::UserAction(UserAction::Cancel)));
+  let res: Result<NumberedPerson> =
+    from_prompt_resume(MockPrompt::new(vec![].into_iter()), &path);
+  assert_eq!(res, Err(Error::SessionMismatch));
+  std::fs::remove_file(&path).unwrap();
+}
+
+#[derive(Deserialize, Serialize)]
+enum Pet {
+  Fish,
+  Dog { name: String },
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "Pet")]
+enum NumberedPet {
+  Fish,
+  Dog { name: u32 },
+}
+
+#[test]
+fn resume_different_variant_type() {
+  let path = std::env::temp_dir().join("spaniel_resume_different_variant");
+  let vec = vec!["yes", "!cancel"].into_iter();
+  let res: Result<Option<Pet>> =
+    from_prompt_resume(MockPrompt::new(vec).with_interactive(), &path);
+  assert!(matches!(res, Err(Error::UserAction(UserAction::Cancel))));
+  let res: Result<Option<NumberedPet>> =
+    from_prompt_resume(MockPrompt::new(vec![].into_iter()), &path);
+  assert!(matches!(res, Err(Error::SessionMismatch)));
+  std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn quiet_replay() {
+  let vec = vec!["Bob", "40", "!undo", "41", "no", "yes"].into_iter();
+  let mut mock = MockPrompt::new(vec).with_interactive();
+  let value: Person = from_prompt(&mut mock).unwrap();
+  assert_eq!(value.age, 41);
+  let log = mock.into_log();
+  let bobs = log
+    .iter()
+    .filter(|e| matches!(e, LogEntry::Response(_, _, _, r) if r == "Bob"));
+  assert_eq!(bobs.count(), 1);
+  assert!(log.contains(&LogEntry::Report(
+    ReportKind::Help,
+    "Replayed responses: 1".into()
+  )));
+}
+
+#[derive(Deserialize, Serialize)]
+struct RawRange {
+  lo: u32,
+  hi: u32,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(try_from = "RawRange")]
+struct Range {
+  lo: u32,
+  hi: u32,
+}
+
+impl TryFrom<RawRange> for Range {
+  type Error = String;
+
+  fn try_from(raw: RawRange) -> std::result::Result<Self, String> {
+    if raw.lo <= raw.hi {
+      Ok(Range {
+        lo: raw.lo,
+        hi: raw.hi,
+      })
+    } else {
+      Err("empty range".into())
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Booking {
+  name: String,
+  range: Range,
+}
+
+#[test]
+fn serde_error_backs_up_to_scope() {
+  let vec = vec!["Bob", "5", "3", "3", "5", "yes"].into_iter();
+  let mut mock = MockPrompt::new(vec).with_interactive();
+  let value: Booking = from_prompt(&mut mock).unwrap();
+  assert_eq!(
+    value,
+    Booking {
+      name: "Bob".into(),
+      range: Range { lo: 3, hi: 5 },
+    }
+  );
+  assert!(mock.into_log().contains(&LogEntry::Report(
+    ReportKind::BadResponse,
+    "Serde Error: empty range (at Booking.range)".into()
+  )));
+}
+
+#[test]
+fn serde_error_not_redone() {
+  let vec = vec!["Bob", "5", "3", "!redo", "3", "5", "yes"].into_iter();
+  let mut mock = MockPrompt::new(vec).with_interactive();
+  let value: Booking = from_prompt(&mut mock).unwrap();
+  assert_eq!(value.range, Range { lo: 3, hi: 5 });
+  assert!(mock.into_log().contains(&LogEntry::Report(
+    ReportKind::Help,
+    "Nothing to redo".into()
+  )));
+}
+
+// Shaped like Flipped, but accepting empty ranges
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "Flipped")]
+struct RawFlipped {
+  range: RawRange,
+  name: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Flipped {
+  range: Range,
+  name: String,
+}
+
+#[test]
+fn resume_keeps_unreplayed_responses() {
+  let path = std::env::temp_dir().join("spaniel_resume_unreplayed");
+  let vec = vec!["5", "3", "Bob", "!cancel"].into_iter();
+  let res: Result<RawFlipped> =
+    from_prompt_resume(MockPrompt::new(vec).with_interactive(), &path);
+  assert!(matches!(res, Err(Error::UserAction(UserAction::Cancel))));
+  // The saved range is rejected part way through replaying
+  let res: Result<Flipped> =
+    from_prompt_resume(MockPrompt::new(vec![].into_iter()), &path);
+  assert!(matches!(res, Err(Error::SerdeError(_, _))));
+  let vec = vec!["yes"].into_iter();
+  let value: RawFlipped =
+    from_prompt_resume(MockPrompt::new(vec).with_interactive(), &path).unwrap();
+  assert_eq!(value.name, "Bob");
+  assert!(!path.exists());
+}
+
+#[test]
+fn responses_round_trip() {
+  let value = Parent {
+    name: "Ann".into(),
+    children: vec![
+      Child {
+        name: "Kid\nTwo".into(),
+        age: 4,
+      },
+      Child {
+        name: "Back\\slash".into(),
+        age: 2,
+      },
+    ],
+  };
+  let responses = to_responses(&value).unwrap();
+  assert_eq!(
+    responses,
+    [
+      "Ann",
+      "yes",
+      "Kid\nTwo",
+      "4",
+      "yes",
+      "Back\\slash",
+      "2",
+      "no"
+    ]
+  );
+  let path = std::env::temp_dir().join("spaniel_responses_round_trip");
+  write_responses(&path, &responses).unwrap();
+  let read: Parent = from_responses(read_responses(&path).unwrap()).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(read, value);
+}
+
+#[test]
+fn responses_run_out() {
+  let responses = vec!["Bob".to_string(), "40".to_string()];
+  let res: Result<Person> = from_responses(responses);
+  assert_eq!(res, Err(Error::OutOfResponses));
+}
+
+#[test]
+fn responses_with_fallback() {
+  let responses = vec!["Bob".to_string(), "40".to_string()];
+  let vec = vec!["yes", "red", "no", "yes"].into_iter();
+  let value: Person = from_responses_or_prompt(
+    responses,
+    MockPrompt::new(vec).with_interactive(),
+  )
+  .unwrap();
+  assert_eq!(
+    value,
+    Person {
+      name: "Bob".into(),
+      age: 40,
+      tags: vec!["red".into()],
+    }
+  );
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum Shape {
+  Circle { radius: u32 },
+  Line(u32, u32),
+  Dot,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Drawing {
+  title: Option<String>,
+  shapes: Vec<Shape>,
+  layers: std::collections::BTreeMap<String, Vec<Shape>>,
+}
+
+#[test]
+fn summary_string() {
+  let mut layers = std::collections::BTreeMap::new();
+  layers.insert("top".to_string(), vec![Shape::Dot]);
+  let value = Drawing {
+    title: None,
+    shapes: vec![Shape::Circle { radius: 2 }, Shape::Line(1, 3)],
+    layers,
+  };
+  assert_eq!(
+    to_summary_string(&value).unwrap(),
+    "title: (none)\n\
+     shapes:\n  \
+       [0]: Circle { radius: 2 }\n  \
+       [1]: Line(1, 3)\n\
+     layers:\n  \
+       top:\n    \
+         [0]: Dot\n"
+  );
+}
+
+#[test]
+fn summary_before_confirm() {
+  let vec = vec!["Ann", "yes", "Kid", "3", "no", "yes"].into_iter();
+  let mut mock = MockPrompt::new(vec).with_interactive();
+  let _: Parent = from_prompt_review(&mut mock).unwrap();
+  let log = mock.into_log();
+  assert_eq!(
+    help_reports(log),
+    [
+      "name: Ann",
+      "children:",
+      "  [0]:",
+      "    name: Kid",
+      "    age: 3",
+    ]
+  );
+}
+
+#[test]
+fn export_fixture() {
+  let path = std::env::temp_dir().join("spaniel_export_fixture");
+  let vec = vec!["Bob", "x", "40", "!undo", "4\"1", "41", "no", "yes"];
+  let mock = MockPrompt::new(vec.into_iter()).with_interactive();
+  let value: Person = from_prompt_export(mock, &path, "crate::Person").unwrap();
+  assert_eq!(value.age, 41);
+  let fixture = std::fs::read_to_string(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(
+    fixture,
+    r#"#[test]
+fn spaniel_session() {
+  use serde_spaniel::testing::ScriptedPrompt;
+
+  let mut prompt = ScriptedPrompt::labelled(vec![
+    ("string", "Bob"),
+    ("u32", "41"),
+    ("Add element?", "no"),
+  ]);
+  let value: crate::Person =
+    serde_spaniel::from_bare_prompt(&mut prompt).unwrap();
+  assert_eq!(prompt.remaining(), 0);
+  assert_eq!(
+    serde_spaniel::to_transcript_string(&value).unwrap(),
+    concat!(
+      "Person {\n",
+      "  name -> string: Bob\n",
+      "  age -> u32: 41\n",
+      "  tags -> seq -> [0] {\n",
+      "    Add element?: no\n",
+      "  }\n",
+      "}\n",
+    )
+  );
+}
+"#
+  );
+}